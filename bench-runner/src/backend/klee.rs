@@ -0,0 +1,162 @@
+use super::{Input, Location, VerifierBackend};
+use crate::options::BenchmarkOptions;
+use crate::verdict::Verdict;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// The KLEE symbolic execution engine, run on whole-program bitcode.
+pub struct Klee {
+    pub program: String,
+    /// The function that KLEE starts executing.
+    pub entry: String,
+}
+
+impl Klee {
+    /// The symbolic inputs of the test case that KLEE wrote for the error
+    /// it found, as printed by `ktest-tool`.
+    ///
+    /// KLEE must have been run with `--output-dir=OUTPUT_DIR`.
+    pub fn counterexample(&self, output_dir: &Path) -> io::Result<Option<String>> {
+        // The test case for an error is `testN.ktest` next to a
+        // `testN.KIND.err` file that describes the error.
+        let mut errors = Vec::new();
+        for entry in fs::read_dir(output_dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "err") {
+                errors.push(path);
+            }
+        }
+        errors.sort();
+        let test = match errors.first().and_then(|path| path.file_name()) {
+            Some(name) => name
+                .to_string_lossy()
+                .split('.')
+                .next()
+                .unwrap()
+                .to_string(),
+            None => return Ok(None),
+        };
+
+        // ktest-tool is installed next to klee.
+        let program = Path::new(&self.program);
+        let ktest_tool = match program.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.join("ktest-tool"),
+            _ => PathBuf::from("ktest-tool"),
+        };
+        let output = Command::new(ktest_tool)
+            .arg(output_dir.join(test).with_extension("ktest"))
+            .output()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let objects: Vec<&str> = stdout
+            .lines()
+            .filter(|line| line.starts_with("object"))
+            .collect();
+        Ok(Some(objects.join("\n")))
+    }
+}
+
+impl VerifierBackend for Klee {
+    fn name(&self) -> &'static str {
+        "klee"
+    }
+
+    fn input(&self) -> Input {
+        Input::Bitcode
+    }
+
+    // KLEE is always bit-precise and checks overflow through the panics
+    // inserted by rustc. The closest thing to a loop bound is a limit
+    // on the number of symbolic branches along a path.
+    fn translate_flags(&self, options: &BenchmarkOptions) -> Vec<String> {
+        let mut flags = Vec::new();
+        if let Some(n) = options.loop_bound {
+            flags.push(format!("--max-depth={}", n));
+        }
+        if let Some(n) = options.timeout {
+            flags.push(format!("--max-time={}s", n));
+        }
+        flags
+    }
+
+    fn rustc_flags(&self, _options: &BenchmarkOptions) -> Vec<String> {
+        vec!["--cfg".to_string(), "feature=\"verifier-klee\"".to_string()]
+    }
+
+    fn features(&self) -> &'static [&'static str] {
+        &["verifier-klee"]
+    }
+
+    fn command(&self, input: &Path, flags: &[String]) -> Command {
+        let mut command = Command::new(&self.program);
+        command
+            .arg(format!("--entry-point={}", self.entry))
+            .arg("--exit-on-error")
+            .arg("--silent-klee-assume")
+            .args(flags)
+            .arg(input);
+        command
+    }
+
+    fn reports_coverage(&self) -> bool {
+        true
+    }
+
+    fn parse_output(&self, output: &Output) -> Verdict {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        for line in stderr.lines() {
+            if line.starts_with("KLEE: HaltTimer invoked")
+                || line.starts_with("KLEE: halting execution, dumping remaining states")
+            {
+                return Verdict::Timeout;
+            } else if line.starts_with("KLEE: ERROR:") {
+                return if line.contains("unreachable") {
+                    Verdict::Reachable
+                } else if line.contains("overflow") {
+                    Verdict::Overflow
+                } else {
+                    Verdict::of_message(line).unwrap_or(Verdict::Error)
+                };
+            } else if !is_klee_noise(line) {
+                return Verdict::Unknown;
+            }
+        }
+        if output.status.success() {
+            Verdict::Verified
+        } else {
+            Verdict::Unknown
+        }
+    }
+
+    fn reports_locations(&self) -> bool {
+        true
+    }
+
+    // Errors are reported as `KLEE: ERROR: FILE:LINE: MESSAGE`, or with
+    // `(location information missing)` if KLEE does not know where. For
+    // assertions and panics, KLEE's location is in klee-annotations and
+    // the message starts with the Rust location, which is preferred.
+    fn failure_location(&self, output: &Output) -> Option<Location> {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let error = stderr
+            .lines()
+            .find_map(|line| line.strip_prefix("KLEE: ERROR: "))?;
+        let message = match error.strip_prefix("(location information missing)") {
+            Some(message) => message,
+            None => error.splitn(3, ':').nth(2).unwrap_or(""),
+        };
+        Location::parse(message).or_else(|| Location::parse(error))
+    }
+}
+
+// Informational messages that KLEE prints on every run.
+fn is_klee_noise(line: &str) -> bool {
+    line.is_empty()
+        || line.starts_with("KLEE: output directory")
+        || line.starts_with("KLEE: Using")
+        || line.starts_with("warning: Linking two modules of different data layouts")
+        || line.starts_with("KLEE: WARNING:")
+        || line.starts_with("KLEE: WARNING ONCE:")
+        || line.starts_with("KLEE: done:")
+}
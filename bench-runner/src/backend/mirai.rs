@@ -0,0 +1,81 @@
+use super::{combined_output, Input, Location, VerifierBackend};
+use crate::options::BenchmarkOptions;
+use crate::verdict::Verdict;
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// The MIRAI abstract interpreter, run as `cargo mirai` on a package.
+pub struct Mirai {
+    /// Cargo, with the `cargo-mirai` subcommand installed.
+    pub program: String,
+}
+
+impl VerifierBackend for Mirai {
+    fn name(&self) -> &'static str {
+        "mirai"
+    }
+
+    fn input(&self) -> Input {
+        Input::Package
+    }
+
+    // These are passed in the MIRAI_FLAGS environment variable.
+    // MIRAI does not unroll loops, and only reports possible overflows
+    // at its most paranoid diagnostics level.
+    fn translate_flags(&self, options: &BenchmarkOptions) -> Vec<String> {
+        let mut flags = Vec::new();
+        if options.integer_overflow {
+            flags.push("--diag=paranoid".to_string());
+        }
+        if let Some(n) = options.timeout {
+            flags.push(format!("--max_analysis_time_for_crate={}", n));
+        }
+        flags
+    }
+
+    // The packages share a target directory so that the dependencies
+    // are only analysed once.
+    fn command(&self, input: &Path, flags: &[String]) -> Command {
+        let mut command = Command::new(&self.program);
+        command
+            .arg("mirai")
+            .current_dir(input)
+            .env("MIRAI_FLAGS", flags.join(" "));
+        if let Some(dir) = input.parent() {
+            command.env("CARGO_TARGET_DIR", dir.join("target"));
+        }
+        command
+    }
+
+    // MIRAI reports its findings as compiler warnings, alongside rustc's
+    // own warnings such as "unreachable pattern", so only MIRAI's wording
+    // is recognised. A failed run without any is not a verdict.
+    fn parse_output(&self, output: &Output) -> Verdict {
+        let text = combined_output(output);
+        let verdict = if text.contains("statement is reachable") {
+            Some(Verdict::Reachable)
+        } else if let Some(verdict) = Verdict::of_message(&text) {
+            Some(verdict)
+        } else if text.contains("false verification condition")
+            || text.contains("possible error")
+            || text.contains("will always panic")
+        {
+            Some(Verdict::Error)
+        } else {
+            None
+        };
+        match verdict {
+            Some(verdict) => verdict,
+            None if output.status.success() => Verdict::Verified,
+            None => Verdict::Unknown,
+        }
+    }
+
+    // Like rustc, MIRAI follows each warning with `--> FILE:LINE:COLUMN`.
+    fn failure_location(&self, output: &Output) -> Option<Location> {
+        combined_output(output)
+            .lines()
+            .find_map(|line| line.trim_start().strip_prefix("--> "))
+            .and_then(Location::parse)
+    }
+}
@@ -0,0 +1,170 @@
+//! Adapters for the verifiers that the runner knows how to drive.
+//!
+//! A backend turns a benchmark (or an artifact built from it) into a
+//! command line, translates the benchmark's options into its own CLI,
+//! and classifies the tool's output as a `Verdict`.
+
+use crate::options::BenchmarkOptions;
+use crate::verdict::Verdict;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+mod klee;
+mod mirai;
+mod native;
+mod random;
+mod smack;
+
+pub use klee::Klee;
+pub use mirai::Mirai;
+pub use native::Native;
+pub use random::Random;
+pub use smack::Smack;
+
+/// What a backend consumes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Input {
+    /// The benchmark's Rust source file.
+    Source,
+    /// Whole-program LLVM bitcode with a `verifier_main` entry point.
+    Bitcode,
+    /// A native executable.
+    Executable,
+    /// A Cargo package whose binary is the benchmark.
+    Package,
+}
+
+pub trait VerifierBackend: Send + Sync {
+    /// Short name used on the command line and in reports.
+    fn name(&self) -> &'static str;
+
+    fn input(&self) -> Input;
+
+    /// Map the benchmark's options to this tool's arguments.
+    ///
+    /// Options that have no equivalent in the tool are dropped.
+    fn translate_flags(&self, options: &BenchmarkOptions) -> Vec<String>;
+
+    /// Flags for rustc when building the input for this backend.
+    fn rustc_flags(&self, _options: &BenchmarkOptions) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Features of the `verifier` crate to build the input with.
+    fn features(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// The command that verifies `input`.
+    fn command(&self, input: &Path, flags: &[String]) -> Command;
+
+    /// Classify the output of the command returned by `command`.
+    fn parse_output(&self, output: &Output) -> Verdict;
+
+    /// Whether the tool prints the `verifier::cover!` points that it
+    /// covers, as `verifier: covered LABEL`.
+    fn reports_coverage(&self) -> bool {
+        false
+    }
+
+    /// Whether `failure_location` finds the location of every failure
+    /// that the tool reports. Otherwise failures are only judged by kind.
+    fn reports_locations(&self) -> bool {
+        false
+    }
+
+    /// Where the tool reported a failure, if it says.
+    fn failure_location(&self, _output: &Output) -> Option<Location> {
+        None
+    }
+}
+
+/// A place in a source file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Location {
+    pub file: PathBuf,
+    pub line: u32,
+}
+
+impl Location {
+    /// Parse `FILE:LINE`, ignoring anything after the line number.
+    pub fn parse(text: &str) -> Option<Location> {
+        let mut parts = text.trim().splitn(3, ':');
+        let file = parts.next().filter(|file| !file.is_empty())?;
+        let line = parts.next()?.parse().ok()?;
+        Some(Location {
+            file: PathBuf::from(file),
+            line,
+        })
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.file.display(), self.line)
+    }
+}
+
+/// Settings shared by all backends.
+#[derive(Clone, Debug, Default)]
+pub struct Settings {
+    /// Overrides the program that the backend invokes.
+    pub program: Option<String>,
+}
+
+/// The names accepted by `by_name`.
+pub const BACKENDS: &[&str] = &["smack", "klee", "mirai", "native", "random"];
+
+pub fn by_name(name: &str, settings: &Settings) -> Option<Box<dyn VerifierBackend>> {
+    let program = |default: &str| {
+        settings
+            .program
+            .clone()
+            .unwrap_or_else(|| default.to_string())
+    };
+    match name {
+        "smack" => Some(Box::new(Smack {
+            program: program("smack"),
+        })),
+        "klee" => Some(Box::new(Klee {
+            program: program("klee"),
+            entry: "verifier_main".to_string(),
+        })),
+        "mirai" => Some(Box::new(Mirai {
+            program: program("cargo"),
+        })),
+        "native" => Some(Box::new(Native)),
+        "random" => Some(Box::new(Random)),
+        _ => None,
+    }
+}
+
+/// The stdout and stderr of a tool as one string.
+pub fn combined_output(output: &Output) -> String {
+    let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locations_are_parsed() {
+        let location = Location::parse(" src/main.rs:12:5: error").unwrap();
+        assert_eq!(location.file, PathBuf::from("src/main.rs"));
+        assert_eq!(location.line, 12);
+        assert_eq!(location.to_string(), "src/main.rs:12");
+        assert_eq!(Location::parse("main.rs:7").unwrap().line, 7);
+    }
+
+    #[test]
+    fn malformed_locations_are_rejected() {
+        assert!(Location::parse("main.rs").is_none());
+        assert!(Location::parse(":12").is_none());
+        assert!(Location::parse("main.rs:twelve").is_none());
+        assert!(Location::parse("").is_none());
+    }
+}
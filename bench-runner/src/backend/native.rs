@@ -0,0 +1,75 @@
+use super::{Input, Location, VerifierBackend};
+use crate::options::BenchmarkOptions;
+use crate::verdict::Verdict;
+use std::os::unix::process::ExitStatusExt;
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// The exit code of a benchmark whose run `assume!` rejected, which is
+/// `VERIFIER_REJECTED_EXIT_CODE` in klee-annotations.
+pub const REJECTED_EXIT_CODE: i32 = 86;
+
+/// Compile the benchmark natively and run it once, like `cargo test`.
+///
+/// This explores a single path: `verifier::nondet!` returns its
+/// placeholder value.
+pub struct Native;
+
+impl VerifierBackend for Native {
+    fn name(&self) -> &'static str {
+        "native"
+    }
+
+    fn input(&self) -> Input {
+        Input::Executable
+    }
+
+    fn translate_flags(&self, _options: &BenchmarkOptions) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn command(&self, input: &Path, flags: &[String]) -> Command {
+        let mut command = Command::new(input);
+        command.args(flags);
+        command
+    }
+
+    fn reports_coverage(&self) -> bool {
+        true
+    }
+
+    // Panics are classified by their message; a panic with a message
+    // that std does not generate is an explicit `panic!`. A run that an
+    // assumption rejected passes vacuously, as it would under KLEE.
+    fn parse_output(&self, output: &Output) -> Verdict {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if output.status.success() || output.status.code() == Some(REJECTED_EXIT_CODE) {
+            Verdict::Verified
+        } else if matches!(
+            output.status.signal(),
+            Some(libc::SIGSEGV) | Some(libc::SIGBUS)
+        ) {
+            Verdict::MemorySafety
+        } else if !stderr.contains("panicked at") {
+            Verdict::Unknown
+        } else {
+            Verdict::of_message(&stderr).unwrap_or(Verdict::Panic)
+        }
+    }
+
+    fn reports_locations(&self) -> bool {
+        true
+    }
+
+    // Current versions of std print `panicked at FILE:LINE:COLUMN:` and
+    // older ones `panicked at 'MESSAGE', FILE:LINE:COLUMN`.
+    fn failure_location(&self, output: &Output) -> Option<Location> {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let start = stderr.find("panicked at ")? + "panicked at ".len();
+        let rest = stderr[start..].lines().next()?;
+        match rest.strip_prefix('\'') {
+            Some(quoted) => Location::parse(&quoted[quoted.rfind("', ")? + 3..]),
+            None => Location::parse(rest),
+        }
+    }
+}
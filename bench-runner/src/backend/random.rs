@@ -0,0 +1,59 @@
+use super::native::REJECTED_EXIT_CODE;
+use super::{Input, Location, Native, VerifierBackend};
+use crate::options::BenchmarkOptions;
+use crate::verdict::Verdict;
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// Compile the benchmark natively with the `verifier-random` feature and
+/// run it many times, with `verifier::nondet!` returning pseudo-random
+/// values, like a property test.
+///
+/// The number of runs and the first seed come from the
+/// `VERIFIER_ITERATIONS` and `VERIFIER_SEED` environment variables.
+/// Otherwise this is the same as the `native` backend.
+pub struct Random;
+
+impl VerifierBackend for Random {
+    fn name(&self) -> &'static str {
+        "random"
+    }
+
+    fn input(&self) -> Input {
+        Input::Executable
+    }
+
+    fn translate_flags(&self, _options: &BenchmarkOptions) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn features(&self) -> &'static [&'static str] {
+        &["verifier-random"]
+    }
+
+    fn command(&self, input: &Path, flags: &[String]) -> Command {
+        Native.command(input, flags)
+    }
+
+    fn reports_coverage(&self) -> bool {
+        true
+    }
+
+    // Unlike a single native run, the runs exit with the rejected code
+    // only if every one of them was rejected, which checks nothing.
+    fn parse_output(&self, output: &Output) -> Verdict {
+        if output.status.code() == Some(REJECTED_EXIT_CODE) {
+            Verdict::Unknown
+        } else {
+            Native.parse_output(output)
+        }
+    }
+
+    fn reports_locations(&self) -> bool {
+        true
+    }
+
+    fn failure_location(&self, output: &Output) -> Option<Location> {
+        Native.failure_location(output)
+    }
+}
@@ -0,0 +1,86 @@
+use super::{combined_output, Input, Location, VerifierBackend};
+use crate::options::{BenchmarkOptions, MemoryModel};
+use crate::verdict::Verdict;
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// SMACK, which reads Rust source directly.
+///
+/// Any program that follows the same output protocol (such as
+/// `benchmarks/run-mirai`) can be used in its place.
+pub struct Smack {
+    pub program: String,
+}
+
+impl VerifierBackend for Smack {
+    fn name(&self) -> &'static str {
+        "smack"
+    }
+
+    fn input(&self) -> Input {
+        Input::Source
+    }
+
+    fn translate_flags(&self, options: &BenchmarkOptions) -> Vec<String> {
+        let mut flags = Vec::new();
+        if let Some(n) = options.loop_bound {
+            flags.push(format!("--unroll={}", n));
+        }
+        if options.bit_precise {
+            flags.push("--bit-precise".to_string());
+        }
+        if options.integer_overflow {
+            flags.push("--integer-overflow".to_string());
+        }
+        if options.memory_model == MemoryModel::NoSplitting {
+            flags.push("--no-memory-splitting".to_string());
+        }
+        if let Some(n) = options.timeout {
+            flags.push(format!("--time-limit={}", n));
+        }
+        flags
+    }
+
+    fn command(&self, input: &Path, flags: &[String]) -> Command {
+        let mut command = Command::new(&self.program);
+        command.arg(input).args(flags);
+        command
+    }
+
+    // These are the status messages recognised by regtest.py.
+    // An empty output means that nothing was found.
+    fn parse_output(&self, output: &Output) -> Verdict {
+        let output = combined_output(output);
+        if output.contains("SMACK timed out") {
+            Verdict::Timeout
+        } else if output.trim().is_empty() || output.contains("found no errors") {
+            Verdict::Verified
+        } else if output.contains("invalid pointer dereference")
+            || output.contains("invalid memory deallocation")
+        {
+            Verdict::MemorySafety
+        } else if output.contains("false verification condition") {
+            Verdict::Error
+        } else if output.contains("with overflow") {
+            Verdict::Overflow
+        } else if output.contains("statement is reachable") {
+            Verdict::Reachable
+        } else {
+            Verdict::Unknown
+        }
+    }
+
+    // The error trace ends with `FILE(LINE,COLUMN): This assertion can fail`.
+    fn failure_location(&self, output: &Output) -> Option<Location> {
+        let output = combined_output(output);
+        let line = output
+            .lines()
+            .find(|line| line.ends_with("This assertion can fail"))?;
+        let (file, rest) = line.split_once('(')?;
+        let (line, _) = rest.split_once(',')?;
+        Some(Location {
+            file: file.trim().into(),
+            line: line.parse().ok()?,
+        })
+    }
+}
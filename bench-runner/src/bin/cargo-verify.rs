@@ -0,0 +1,262 @@
+use bench_runner::backend::{combined_output, Input, Klee, Native, Random, VerifierBackend};
+use bench_runner::compile::Artifact;
+use bench_runner::exec::{self, Exceeded, Limits};
+use bench_runner::harness::{self, Harness};
+use bench_runner::options::BenchmarkOptions;
+use bench_runner::verdict::Verdict;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+
+const USAGE: &str = "\
+usage: cargo verify [options] [HARNESS...]
+
+Builds the package with its verification harnesses, the functions marked
+with `#[verifier::harness]`, and verifies each of them (or those named).
+
+options:
+    --backend NAME         klee, native or random (default: klee)
+    --verifier PROGRAM     program that the backend invokes instead of its default
+    --manifest-path PATH   the package's Cargo.toml (default: ./Cargo.toml)
+    --target-dir DIR       where the package is built (default: target/verify
+                           in the package)
+    --time-limit SECS      cap on the CPU time of each harness
+    --memory-limit MB      cap on the memory of each harness
+    -v, --verbose          show verifier output for harnesses that do not verify
+    -h, --help             show this message";
+
+struct Options {
+    backend: String,
+    program: Option<String>,
+    manifest_path: PathBuf,
+    target_dir: Option<PathBuf>,
+    limits: Limits,
+    verbose: bool,
+    harnesses: Vec<String>,
+}
+
+impl Options {
+    // Each harness is a separate KLEE entry point.
+    fn klee(&self, harness: &Harness) -> Klee {
+        Klee {
+            program: self.program.clone().unwrap_or_else(|| "klee".to_string()),
+            entry: harness.symbol(),
+        }
+    }
+}
+
+fn parse_args(args: Vec<String>) -> Options {
+    let mut options = Options {
+        backend: "klee".to_string(),
+        program: None,
+        manifest_path: PathBuf::from("Cargo.toml"),
+        target_dir: None,
+        limits: Limits::default(),
+        verbose: false,
+        harnesses: Vec::new(),
+    };
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().unwrap_or_else(|| usage_error());
+        match arg.as_str() {
+            "--backend" => options.backend = value(),
+            "--verifier" => options.program = Some(value()),
+            "--manifest-path" => options.manifest_path = PathBuf::from(value()),
+            "--target-dir" => options.target_dir = Some(PathBuf::from(value())),
+            "--time-limit" => options.limits.time = Some(number(&value())),
+            "--memory-limit" => options.limits.memory = Some(number(&value())),
+            "-v" | "--verbose" => options.verbose = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0)
+            }
+            _ if arg.starts_with('-') => usage_error(),
+            _ => options.harnesses.push(arg),
+        }
+    }
+    options
+}
+
+fn number<T: std::str::FromStr>(value: &str) -> T {
+    value.parse().unwrap_or_else(|_| usage_error())
+}
+
+fn usage_error() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2)
+}
+
+fn fatal(message: impl std::fmt::Display) -> ! {
+    eprintln!("cargo-verify: {}", message);
+    process::exit(2)
+}
+
+fn main() {
+    // Cargo runs `cargo-verify verify ARGS...` for `cargo verify ARGS...`.
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("verify") {
+        args.remove(0);
+    }
+    let options = parse_args(args);
+
+    let package_dir = options.manifest_path.parent().unwrap_or(Path::new(""));
+    let target_dir = options
+        .target_dir
+        .clone()
+        .unwrap_or_else(|| package_dir.join("target/verify"))
+        .join(&options.backend);
+    // The backends are made per harness rather than with
+    // `backend::by_name`, since the entry point differs.
+    let make_backend = |harness: &Harness| -> Box<dyn VerifierBackend> {
+        match options.backend.as_str() {
+            "klee" => Box::new(options.klee(harness)),
+            "native" => Box::new(Native),
+            "random" => Box::new(Random),
+            name => fatal(format_args!(
+                "the {} backend cannot verify harnesses (expected klee, native or random)",
+                name
+            )),
+        }
+    };
+
+    let mut harnesses = harness::discover(package_dir).unwrap_or_else(|e| fatal(e));
+    if !options.harnesses.is_empty() {
+        for name in &options.harnesses {
+            if !harnesses.iter().any(|harness| &harness.name == name) {
+                fatal(format_args!("no harness named '{}'", name));
+            }
+        }
+        harnesses.retain(|harness| options.harnesses.contains(&harness.name));
+    }
+    let first = match harnesses.first() {
+        Some(harness) => harness,
+        None => {
+            println!("no harnesses found");
+            return;
+        }
+    };
+
+    eprintln!("building harnesses for {}", options.backend);
+    let artifacts = harness::build(package_dir, &target_dir, &*make_backend(first))
+        .unwrap_or_else(|e| fatal(e));
+
+    let (mut verified, mut failed, mut unknown) = (0, 0, 0);
+    for harness in &harnesses {
+        let backend = make_backend(harness);
+        let verdict = verify(harness, &*backend, &artifacts, &target_dir, &options);
+        if verdict == Verdict::Verified {
+            verified += 1;
+        } else if verdict.is_failure() {
+            failed += 1;
+        } else {
+            unknown += 1;
+        }
+    }
+
+    println!();
+    println!("VERIFIED count {}", verified);
+    println!("FAILED count   {}", failed);
+    println!("UNKNOWN count  {}", unknown);
+    if failed > 0 || unknown > 0 {
+        process::exit(1)
+    }
+}
+
+// Verify one harness and print its result.
+fn verify(
+    harness: &Harness,
+    backend: &dyn VerifierBackend,
+    artifacts: &[Artifact],
+    target_dir: &Path,
+    options: &Options,
+) -> Verdict {
+    let executable = harness
+        .executable(artifacts)
+        .unwrap_or_else(|| fatal(format_args!("no test executable contains {}", harness.name)));
+
+    let mut flags = backend.translate_flags(&BenchmarkOptions::default());
+    let output_dir = target_dir.join("klee-out").join(&harness.name);
+    let input = match backend.input() {
+        Input::Bitcode => {
+            // KLEE refuses to write into an existing directory.
+            if output_dir.exists() {
+                fs::remove_dir_all(&output_dir).unwrap_or_else(|e| fatal(e));
+            }
+            fs::create_dir_all(target_dir.join("klee-out")).unwrap_or_else(|e| fatal(e));
+            flags.push(format!("--output-dir={}", output_dir.display()));
+            executable.with_extension("bc")
+        }
+        // Run the harness alone, as a test, showing its panic message.
+        // Test names are filters that match any test containing them, so
+        // the full name is matched exactly.
+        _ => {
+            let listing = Command::new(executable)
+                .arg("--list")
+                .output()
+                .unwrap_or_else(|e| fatal(format_args!("cannot list the tests: {}", e)));
+            let test = harness
+                .test_name(&String::from_utf8_lossy(&listing.stdout))
+                .unwrap_or_else(|| harness.symbol());
+            flags.extend(
+                [test.as_str(), "--exact", "--nocapture", "--test-threads=1"]
+                    .iter()
+                    .map(|flag| flag.to_string()),
+            );
+            executable.to_path_buf()
+        }
+    };
+
+    let execution = exec::execute(backend.command(&input, &flags), options.limits)
+        .unwrap_or_else(|e| fatal(format_args!("cannot run {}: {}", backend.name(), e)));
+    let verdict = match execution.exceeded {
+        Some(Exceeded::Time) => Verdict::Timeout,
+        Some(Exceeded::Memory) => Verdict::OutOfMemory,
+        // A test that is not found is not run, which passes.
+        None if String::from_utf8_lossy(&execution.output.stdout).contains("running 0 tests") => {
+            Verdict::Unknown
+        }
+        None => backend.parse_output(&execution.output),
+    };
+
+    let status = if verdict == Verdict::Verified {
+        "VERIFIED"
+    } else if verdict.is_failure() {
+        "FAILED"
+    } else {
+        "UNKNOWN"
+    };
+    let mut got = verdict.to_string();
+    if verdict.is_failure() {
+        if let Some(location) = backend.failure_location(&execution.output) {
+            got.push_str(&format!(" at {}", location));
+        }
+    }
+    println!(
+        "{:<11} {} ({}:{})  [{:.2}s] {}",
+        status,
+        harness.name,
+        harness.path.display(),
+        harness.line,
+        execution.elapsed.as_secs_f64(),
+        got
+    );
+
+    if verdict.is_failure() && backend.input() == Input::Bitcode {
+        match options.klee(harness).counterexample(&output_dir) {
+            Ok(Some(inputs)) if !inputs.is_empty() => {
+                println!("    counterexample:");
+                for line in inputs.lines() {
+                    println!("        {}", line);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => println!("    no counterexample: {}", e),
+        }
+    }
+    if options.verbose && verdict != Verdict::Verified {
+        for line in combined_output(&execution.output).lines() {
+            println!("    {}", line);
+        }
+    }
+    verdict
+}
@@ -0,0 +1,179 @@
+//! Comparing the reports of two runs, for example before and after
+//! upgrading a verifier.
+
+use crate::report::{Record, Report};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// How one benchmark's result changed between two runs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// The benchmark used to pass and no longer does.
+    Regression,
+    /// The benchmark did not pass and now does.
+    Fix,
+    /// The verifier now runs out of time.
+    NewTimeout,
+    /// The verdict changed but the benchmark neither started nor
+    /// stopped passing.
+    Changed,
+    /// The verdict is unchanged but the verifier got much slower.
+    Slower,
+}
+
+#[derive(Debug)]
+pub struct Change<'a> {
+    pub kind: ChangeKind,
+    pub old: &'a Record,
+    pub new: &'a Record,
+}
+
+impl Change<'_> {
+    /// A benchmark that should fail is now reported as verified.
+    /// Benchmarks that expect `unknown` have no expected verdict.
+    pub fn is_unsound(&self) -> bool {
+        !matches!(self.new.expected.as_str(), "verified" | "unknown")
+            && self.new.verdict == "verified"
+            && self.old.verdict != "verified"
+    }
+}
+
+/// Totals for one category directory.
+#[derive(Debug, Default)]
+pub struct CategorySummary {
+    pub regressions: usize,
+    pub fixes: usize,
+    pub new_timeouts: usize,
+    pub old_time: f64,
+    pub new_time: f64,
+}
+
+pub struct Comparison<'a> {
+    pub changes: Vec<Change<'a>>,
+    pub categories: BTreeMap<&'a str, CategorySummary>,
+    /// Benchmarks that are only in the old report.
+    pub removed: Vec<&'a Record>,
+    /// Benchmarks that are only in the new report.
+    pub added: Vec<&'a Record>,
+}
+
+impl<'a> Comparison<'a> {
+    /// Compare two reports. A benchmark counts as much slower if its
+    /// time grew by more than `slowdown` times and by at least a second.
+    pub fn new(old: &'a Report, new: &'a Report, slowdown: f64) -> Comparison<'a> {
+        let old_by_file: BTreeMap<&str, &Record> =
+            old.results.iter().map(|r| (r.file.as_str(), r)).collect();
+        let new_by_file: BTreeMap<&str, &Record> =
+            new.results.iter().map(|r| (r.file.as_str(), r)).collect();
+
+        let mut comparison = Comparison {
+            changes: Vec::new(),
+            categories: BTreeMap::new(),
+            removed: old_by_file
+                .iter()
+                .filter(|(file, _)| !new_by_file.contains_key(*file))
+                .map(|(_, r)| *r)
+                .collect(),
+            added: Vec::new(),
+        };
+
+        for (file, new) in new_by_file {
+            let old = match old_by_file.get(file) {
+                Some(old) => *old,
+                None => {
+                    comparison.added.push(new);
+                    continue;
+                }
+            };
+            let summary = comparison.categories.entry(&new.category).or_default();
+            summary.old_time += old.time;
+            summary.new_time += new.time;
+
+            let passed = |r: &Record| r.outcome == "PASSED";
+            let kind = if passed(old) && !passed(new) {
+                Some(ChangeKind::Regression)
+            } else if !passed(old) && passed(new) {
+                Some(ChangeKind::Fix)
+            } else if new.verdict == "timeout" && old.verdict != "timeout" {
+                Some(ChangeKind::NewTimeout)
+            } else if old.verdict != new.verdict {
+                Some(ChangeKind::Changed)
+            } else if new.time > old.time * slowdown && new.time - old.time >= 1.0 {
+                Some(ChangeKind::Slower)
+            } else {
+                None
+            };
+            match kind {
+                Some(ChangeKind::Regression) => summary.regressions += 1,
+                Some(ChangeKind::Fix) => summary.fixes += 1,
+                _ => {}
+            }
+            if new.verdict == "timeout" && old.verdict != "timeout" {
+                summary.new_timeouts += 1;
+            }
+            if let Some(kind) = kind {
+                comparison.changes.push(Change { kind, old, new });
+            }
+        }
+        comparison
+    }
+
+    pub fn soundness_regressions(&self) -> impl Iterator<Item = &Change<'a>> {
+        self.changes.iter().filter(|c| c.is_unsound())
+    }
+
+    /// A table of the totals per category followed by each change.
+    pub fn to_table(&self) -> String {
+        let mut table = String::new();
+        let _ = writeln!(
+            table,
+            "{:<16} {:>11} {:>6} {:>12} {:>10} {:>10} {:>9}",
+            "CATEGORY", "REGRESSIONS", "FIXES", "NEW TIMEOUTS", "OLD TIME", "NEW TIME", "DELTA"
+        );
+        for (category, s) in &self.categories {
+            let _ = writeln!(
+                table,
+                "{:<16} {:>11} {:>6} {:>12} {:>9.2}s {:>9.2}s {:>+8.2}s",
+                category,
+                s.regressions,
+                s.fixes,
+                s.new_timeouts,
+                s.old_time,
+                s.new_time,
+                s.new_time - s.old_time
+            );
+        }
+
+        if !self.changes.is_empty() {
+            table.push('\n');
+        }
+        for change in &self.changes {
+            let label = match change.kind {
+                _ if change.is_unsound() => "UNSOUND",
+                ChangeKind::Regression => "REGRESSION",
+                ChangeKind::Fix => "FIXED",
+                ChangeKind::NewTimeout => "TIMEOUT",
+                ChangeKind::Changed => "CHANGED",
+                ChangeKind::Slower => "SLOWER",
+            };
+            let _ = writeln!(
+                table,
+                "{:<11} {}  {} -> {} (expected {})  [{:.2}s -> {:.2}s]",
+                label,
+                change.new.file,
+                change.old.verdict,
+                change.new.verdict,
+                change.new.expected,
+                change.old.time,
+                change.new.time
+            );
+        }
+        for r in &self.removed {
+            let _ = writeln!(table, "{:<11} {}", "REMOVED", r.file);
+        }
+        for r in &self.added {
+            let _ = writeln!(table, "{:<11} {}  {}", "ADDED", r.file, r.verdict);
+        }
+        table
+    }
+}
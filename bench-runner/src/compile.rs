@@ -0,0 +1,374 @@
+//! Building the inputs that backends consume from a benchmark file.
+//!
+//! Each benchmark is built as a small Cargo package that depends on the
+//! `verifier`, `symbolic` and `klee-annotations` crates by path, like the
+//! packages that `benchmarks/run-mirai` creates. The packages for one
+//! backend share a target directory so that those crates are only built
+//! once, and each artifact has a stamp file holding a hash of everything
+//! that went into it so that unchanged benchmarks are not rebuilt.
+
+use crate::backend::{Input, VerifierBackend};
+use crate::spec::{self, BenchmarkSpec};
+use serde::Deserialize;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// The crates that benchmarks may use, as directories under
+/// `Builder::crates_dir`.
+pub const CRATES: &[&str] = &["verifier", "symbolic", "klee-annotations"];
+
+// Appended to every benchmark built for KLEE so that panics are
+// reported as errors before `main` runs. The paths that return from
+// `main` cover `cover::END_OF_MAIN`.
+const KLEE_WRAPPER: &str = r#"
+#[no_mangle]
+pub extern "C" fn verifier_main() {
+    klee_annotations::verifier_set_panic_hook();
+    main();
+    klee_annotations::verifier_cover(true, "end of main");
+}
+"#;
+
+// Used instead of including the benchmark directly when it is built as
+// an executable, so that the runs that return from `main` cover
+// `cover::END_OF_MAIN`. RUN calls `benchmark::verifier_main`.
+const NATIVE_WRAPPER: &str = r#"mod benchmark {
+    include!(BENCHMARK);
+
+    pub fn verifier_main() {
+        main();
+        verifier::cover!(true, "end of main");
+    }
+}
+
+fn main() {
+    RUN;
+}
+"#;
+
+/// Flags for building whole-program bitcode, as in run-mirai. Cargo
+/// tells rustc not to embed bitcode in libraries, which LTO needs. Nothing
+/// is linked, so the linker is replaced by a program that does nothing.
+pub const BITCODE_FLAGS: &[&str] = &[
+    "-Clinker=/bin/true",
+    "-Clto",
+    "-Cembed-bitcode=yes",
+    "--emit=llvm-bc",
+    "-Cpanic=abort",
+];
+
+/// A benchmark that could not be built.
+#[derive(Debug)]
+pub struct CompileError {
+    pub output: String,
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "compilation failed:\n{}", self.output)
+    }
+}
+
+impl From<io::Error> for CompileError {
+    fn from(e: io::Error) -> Self {
+        CompileError {
+            output: e.to_string(),
+        }
+    }
+}
+
+/// Builds benchmarks into the inputs that backends need.
+#[derive(Debug)]
+pub struct Builder {
+    /// The directory containing the `verifier`, `symbolic` and
+    /// `klee-annotations` crates.
+    pub crates_dir: PathBuf,
+    /// Where packages, target directories and artifacts are placed.
+    pub work_dir: PathBuf,
+    // A hash of the sources of the crates, computed on first use.
+    crates_hash: OnceLock<u64>,
+}
+
+impl Builder {
+    pub fn new(crates_dir: PathBuf, work_dir: PathBuf) -> Builder {
+        Builder {
+            crates_dir,
+            work_dir,
+            crates_hash: OnceLock::new(),
+        }
+    }
+
+    /// Build the input that `backend` needs for `spec`.
+    pub fn prepare(
+        &self,
+        spec: &BenchmarkSpec,
+        backend: &dyn VerifierBackend,
+    ) -> Result<PathBuf, CompileError> {
+        let input = backend.input();
+        if input == Input::Source {
+            return Ok(spec.path.clone());
+        }
+
+        let name = package_name(&spec.path);
+        let package = self.work_dir.join(backend.name()).join(&name);
+        fs::create_dir_all(package.join("src"))?;
+        let manifest = self.manifest(&name, backend.features())?;
+        // The benchmark is included rather than copied so that verifiers
+        // report failures at its own path and line numbers.
+        let source = format!("{:?}", fs::canonicalize(&spec.path)?);
+        let main = match input {
+            Input::Bitcode => format!("include!({});\n{}", source, KLEE_WRAPPER),
+            // With `verifier-random`, `main` runs in a loop that discards
+            // the runs that `assume!` rejects.
+            Input::Executable if backend.features().contains(&"verifier-random") => NATIVE_WRAPPER
+                .replace("RUN", "verifier::native::run(benchmark::verifier_main)")
+                .replace("BENCHMARK", &source),
+            Input::Executable => NATIVE_WRAPPER
+                .replace("RUN", "benchmark::verifier_main()")
+                .replace("BENCHMARK", &source),
+            _ => format!("include!({});\n", source),
+        };
+        write_if_changed(&package.join("Cargo.toml"), &manifest)?;
+        write_if_changed(&package.join("src/main.rs"), &main)?;
+        if input == Input::Package {
+            return Ok(package);
+        }
+
+        // The overflow benchmarks overflow in constant expressions,
+        // which rustc rejects by default.
+        let mut rustflags = vec!["-Warithmetic-overflow".to_string()];
+        rustflags.extend(backend.rustc_flags(&spec.options));
+        if input == Input::Bitcode {
+            rustflags.extend(BITCODE_FLAGS.iter().map(|flag| flag.to_string()));
+        }
+        let rustflags = rustflags.join(" ");
+
+        let mut hasher = DefaultHasher::new();
+        (&manifest, &main, &rustflags).hash(&mut hasher);
+        fs::read(&spec.path)?.hash(&mut hasher);
+        self.crates_hash()?.hash(&mut hasher);
+        let hash = format!("{:016x}", hasher.finish());
+
+        let artifact = package.join(match input {
+            Input::Bitcode => format!("{}.bc", name),
+            _ => name.clone(),
+        });
+        let stamp = package.join("stamp");
+        if artifact.is_file() && fs::read_to_string(&stamp).ok().as_deref() == Some(&hash) {
+            return Ok(artifact);
+        }
+
+        let target_dir = self.work_dir.join(backend.name()).join("target");
+        let built = cargo_build(&package, &target_dir, &rustflags)?;
+        let built = match input {
+            Input::Bitcode => find_bitcode(&target_dir, &name)?,
+            _ => built,
+        };
+        fs::copy(built, &artifact)?;
+        fs::write(stamp, hash)?;
+        Ok(artifact)
+    }
+
+    fn manifest(&self, name: &str, features: &[&str]) -> io::Result<String> {
+        let path = |krate: &str| fs::canonicalize(self.crates_dir.join(krate));
+        let verifier_features = if features.is_empty() {
+            String::new()
+        } else {
+            format!(", features = {:?}", features)
+        };
+        Ok(format!(
+            r#"[package]
+name = "{name}"
+version = "0.0.0"
+edition = "2018"
+publish = false
+
+[dependencies]
+klee-annotations = {{ path = {annotations:?}, features = ["verifier-panic-handler"] }}
+verifier = {{ path = {verifier:?}{verifier_features} }}
+symbolic = {{ path = {symbolic:?} }}
+
+# Keep the package out of any workspace that encloses the work directory.
+[workspace]
+
+[profile.dev]
+overflow-checks = true
+incremental = false
+"#,
+            name = name,
+            annotations = path("klee-annotations")?,
+            verifier = path("verifier")?,
+            verifier_features = verifier_features,
+            symbolic = path("symbolic")?,
+        ))
+    }
+
+    // Changing any of the crates invalidates every stamp.
+    fn crates_hash(&self) -> io::Result<u64> {
+        if let Some(hash) = self.crates_hash.get() {
+            return Ok(*hash);
+        }
+        let mut files = Vec::new();
+        for krate in CRATES {
+            let dir = self.crates_dir.join(krate);
+            files.push(dir.join("Cargo.toml"));
+            spec::walk(&dir.join("src"), &mut files)?;
+        }
+        files.sort();
+        let mut hasher = DefaultHasher::new();
+        for file in files {
+            file.hash(&mut hasher);
+            fs::read(&file)?.hash(&mut hasher);
+        }
+        Ok(*self.crates_hash.get_or_init(|| hasher.finish()))
+    }
+}
+
+/// An artifact that Cargo reports building.
+#[derive(Debug, Deserialize)]
+pub struct Artifact {
+    pub target: Target,
+    pub profile: Profile,
+    pub executable: Option<PathBuf>,
+}
+
+/// The package target that an artifact was built from.
+#[derive(Debug, Deserialize)]
+pub struct Target {
+    pub name: String,
+    pub kind: Vec<String>,
+    pub src_path: PathBuf,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Profile {
+    /// Set for test executables.
+    pub test: bool,
+}
+
+// The messages that Cargo prints with `--message-format=json`.
+#[derive(Deserialize)]
+struct Message {
+    reason: String,
+    #[serde(flatten)]
+    artifact: Option<Artifact>,
+}
+
+/// Run a Cargo build command such as `cargo build` or
+/// `cargo test --no-run`, returning the artifacts that it reports.
+///
+/// The build is for the host, but with an explicit `--target` so that
+/// `RUSTFLAGS` do not apply to proc macros such as `verifier-macros`.
+/// Artifacts are therefore under `TARGET_DIR/HOST/debug`.
+pub fn cargo(mut command: Command) -> Result<Vec<Artifact>, CompileError> {
+    let output = command
+        .arg("--target")
+        .arg(host()?)
+        .arg("--message-format=json")
+        .output()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() {
+        // Compiler messages are on stdout in JSON, with a readable
+        // rendering of each. Only the errors are of interest.
+        let mut text = String::from_utf8_lossy(&output.stderr).into_owned();
+        for line in stdout.lines() {
+            let value: serde_json::Value = serde_json::from_str(line).unwrap_or_default();
+            if value["message"]["level"] == "error" {
+                if let Some(rendered) = value["message"]["rendered"].as_str() {
+                    text.push_str(rendered);
+                }
+            }
+        }
+        return Err(CompileError { output: text });
+    }
+    Ok(stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Message>(line).ok())
+        .filter(|message| message.reason == "compiler-artifact")
+        .filter_map(|message| message.artifact)
+        .collect())
+}
+
+// Build the package's binary, returning the executable.
+fn cargo_build(
+    package: &Path,
+    target_dir: &Path,
+    rustflags: &str,
+) -> Result<PathBuf, CompileError> {
+    let mut command = Command::new("cargo");
+    command
+        .arg("build")
+        .arg("--manifest-path")
+        .arg(package.join("Cargo.toml"))
+        .env("CARGO_TARGET_DIR", target_dir)
+        .env("RUSTFLAGS", rustflags);
+    cargo(command)?
+        .into_iter()
+        .find_map(|artifact| artifact.executable)
+        .ok_or_else(|| CompileError {
+            output: "cargo did not report an executable".to_string(),
+        })
+}
+
+/// The target triple of the host, from `rustc -vV`.
+pub fn host() -> io::Result<&'static str> {
+    static HOST: OnceLock<String> = OnceLock::new();
+    if let Some(host) = HOST.get() {
+        return Ok(host);
+    }
+    let output = Command::new("rustc").arg("-vV").output()?;
+    let host = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.strip_prefix("host: "))
+        .map(str::to_string)
+        .ok_or_else(|| io::Error::other("rustc did not say what the host is"))?;
+    Ok(HOST.get_or_init(|| host))
+}
+
+// rustc writes the bitcode of a binary next to its other intermediate
+// files, with a hash in its name. The newest is the one just built.
+fn find_bitcode(target_dir: &Path, name: &str) -> io::Result<PathBuf> {
+    let prefix = format!("{}-", name.replace('-', "_"));
+    let mut newest = None;
+    for entry in fs::read_dir(target_dir.join(host()?).join("debug/deps"))? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        let file_name = file_name.to_string_lossy();
+        if file_name.starts_with(&prefix) && file_name.ends_with(".bc") {
+            let modified = entry.metadata()?.modified()?;
+            if newest.as_ref().is_none_or(|(time, _)| modified > *time) {
+                newest = Some((modified, entry.path()));
+            }
+        }
+    }
+    newest
+        .map(|(_, path)| path)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "rustc did not write any bitcode"))
+}
+
+// Writing only changed files leaves Cargo's fingerprints alone.
+fn write_if_changed(path: &Path, text: &str) -> io::Result<()> {
+    if fs::read_to_string(path).ok().as_deref() != Some(text) {
+        fs::write(path, text)?;
+    }
+    Ok(())
+}
+
+// A package name that is unique for each benchmark path.
+fn package_name(path: &Path) -> String {
+    path.with_extension("")
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(name) => name.to_str(),
+            _ => None,
+        })
+        .map(|name| name.replace(|c: char| !c.is_ascii_alphanumeric(), "_"))
+        .collect::<Vec<_>>()
+        .join("-")
+}
@@ -0,0 +1,45 @@
+//! Per-directory defaults from `config.yml` files.
+//!
+//! These are the same files that regtest.py reads: every `config.yml`
+//! in a directory containing a benchmark applies to it, with files in
+//! deeper directories overriding those above them and `flags` being
+//! appended rather than replaced.
+
+use crate::spec::SpecError;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct DirectoryConfig {
+    /// CPU time limit in seconds.
+    pub time_limit: Option<u64>,
+    /// Memory limit in megabytes.
+    pub memory_limit: Option<u64>,
+    pub skip: Option<bool>,
+    #[serde(default)]
+    pub flags: Vec<String>,
+}
+
+impl DirectoryConfig {
+    /// The merged configuration for the benchmark at `path`.
+    pub fn for_benchmark(path: &Path) -> Result<DirectoryConfig, SpecError> {
+        let mut merged = DirectoryConfig::default();
+        let mut dirs: Vec<&Path> = path.ancestors().skip(1).collect();
+        dirs.reverse();
+        for dir in dirs {
+            let file = dir.join("config.yml");
+            if !file.is_file() {
+                continue;
+            }
+            let config: DirectoryConfig = serde_yaml::from_str(&fs::read_to_string(&file)?)
+                .map_err(|e| SpecError::Config(file.clone(), e.to_string()))?;
+            merged.time_limit = config.time_limit.or(merged.time_limit);
+            merged.memory_limit = config.memory_limit.or(merged.memory_limit);
+            merged.skip = config.skip.or(merged.skip);
+            merged.flags.extend(config.flags);
+        }
+        Ok(merged)
+    }
+}
@@ -0,0 +1,61 @@
+//! Cover points, the places that a benchmark marks with
+//! `verifier::cover!(CONDITION, "LABEL")`.
+//!
+//! A benchmark that verifies without covering its cover points may only
+//! verify because its assumptions rule out the runs that reach them.
+//! Backends that support `cover!` print `verifier: covered LABEL` for
+//! each point that a run reaches with its condition true.
+
+use crate::spec;
+use std::collections::BTreeSet;
+
+/// The label of the cover point that the runner puts after `main`
+/// returns, which no run covers if every run is rejected by an
+/// assumption.
+pub const END_OF_MAIN: &str = "end of main";
+
+/// A `verifier::cover!` in the source of a benchmark.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CoverPoint {
+    pub label: String,
+    pub line: u32,
+}
+
+/// The cover points in `source` whose label is a string literal on the
+/// same line as the `cover!`.
+pub fn points(source: &str) -> Vec<CoverPoint> {
+    let mut points = Vec::new();
+    for (number, line) in (1..).zip(source.lines()) {
+        if line.trim_start().starts_with("//") {
+            continue;
+        }
+        let arguments = match line.find("cover!(") {
+            Some(start) => &line[start + "cover!(".len()..],
+            None => continue,
+        };
+        let label = arguments
+            .rfind(", \"")
+            .and_then(|comma| spec::quoted_string(&arguments[comma + 2..]));
+        if let Some((label, _)) = label {
+            points.push(CoverPoint {
+                label,
+                line: number,
+            });
+        }
+    }
+    points
+}
+
+/// The labels of the cover points that the verifier's output says were
+/// covered.
+pub fn covered(output: &str) -> BTreeSet<String> {
+    const MARKER: &str = "verifier: covered ";
+    output
+        .lines()
+        .filter_map(|line| {
+            line.find(MARKER)
+                .map(|i| line[i + MARKER.len()..].trim_end())
+        })
+        .map(str::to_string)
+        .collect()
+}
@@ -0,0 +1,258 @@
+//! Running a verifier process and measuring the resources it uses.
+//!
+//! Each verifier runs in its own process group so that the whole tree
+//! of processes it starts can be measured and killed together.
+
+use std::fs;
+use std::io::{self, Read};
+use std::os::unix::process::{CommandExt, ExitStatusExt};
+use std::process::{Command, ExitStatus, Output, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Resource limits for one verifier run.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Limits {
+    /// CPU time in seconds, summed over the process tree.
+    pub time: Option<u64>,
+    /// Resident memory in megabytes, summed over the process tree. It
+    /// also bounds the address space of each process.
+    pub memory: Option<u64>,
+}
+
+impl Limits {
+    /// The tighter of each pair of limits.
+    pub fn min(self, other: Limits) -> Limits {
+        fn min(a: Option<u64>, b: Option<u64>) -> Option<u64> {
+            match (a, b) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                _ => a.or(b),
+            }
+        }
+        Limits {
+            time: min(self.time, other.time),
+            memory: min(self.memory, other.memory),
+        }
+    }
+}
+
+/// Which limit a process was killed for exceeding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Exceeded {
+    Time,
+    Memory,
+}
+
+/// The output of a process together with what it cost.
+#[derive(Debug)]
+pub struct Execution {
+    pub output: Output,
+    pub elapsed: Duration,
+    /// Peak resident set size in kilobytes.
+    pub max_rss_kb: u64,
+    /// Set if the process tree was killed for exceeding a limit.
+    pub exceeded: Option<Exceeded>,
+}
+
+// How often the process tree is measured.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// A process that is blocked uses no CPU time, so it is also killed once
+// the wall-clock time exceeds the CPU time limit by this much.
+const WALL_CLOCK_GRACE: Duration = Duration::from_secs(30);
+
+/// Run `command` to completion, capturing its output, and kill it if it
+/// exceeds `limits`.
+pub fn execute(mut command: Command, limits: Limits) -> io::Result<Execution> {
+    // A process that allocates past the limit then fails straight away
+    // instead of growing until the next poll, which is still needed for
+    // a tree of processes that exceeds the limit together.
+    if let Some(megabytes) = limits.memory {
+        let bytes = (megabytes * 1024 * 1024) as libc::rlim_t;
+        unsafe {
+            command.pre_exec(move || {
+                let limit = libc::rlimit {
+                    rlim_cur: bytes,
+                    rlim_max: bytes,
+                };
+                if libc::setrlimit(libc::RLIMIT_AS, &limit) == 0 {
+                    Ok(())
+                } else {
+                    Err(io::Error::last_os_error())
+                }
+            });
+        }
+    }
+
+    let start = Instant::now();
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0)
+        .spawn()?;
+    let pgid = child.id() as libc::pid_t;
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let (done, finished) = mpsc::channel();
+    let watchdog = thread::spawn(move || watch(pgid, limits, start, finished));
+
+    // Wait with wait4 rather than Child::wait to find out how much
+    // memory the process used.
+    let waited = wait4(pgid);
+    let _ = done.send(());
+    let exceeded = watchdog.join().unwrap_or(None);
+    let elapsed = start.elapsed();
+
+    // Anything the verifier left running would keep the pipes open.
+    kill_group(pgid);
+    let (status, max_rss_kb) = waited?;
+    let stderr = stderr.join().unwrap_or_default();
+    let exceeded = match exceeded {
+        None if limits.memory.is_some() && !status.success() && allocation_failed(&stderr) => {
+            Some(Exceeded::Memory)
+        }
+        exceeded => exceeded,
+    };
+
+    Ok(Execution {
+        output: Output {
+            status,
+            stdout: stdout.join().unwrap_or_default(),
+            stderr,
+        },
+        elapsed,
+        max_rss_kb,
+        exceeded,
+    })
+}
+
+// Whether a process reported that it could not allocate memory, as it
+// does when it runs into the address space limit: Rust aborts with
+// "memory allocation of N bytes failed", C++ throws `std::bad_alloc`,
+// and KLEE and .NET say "out of memory".
+fn allocation_failed(stderr: &[u8]) -> bool {
+    let text = String::from_utf8_lossy(stderr).to_lowercase();
+    [
+        "memory allocation of",
+        "bad_alloc",
+        "out of memory",
+        "outofmemory",
+    ]
+    .iter()
+    .any(|wording| text.contains(wording))
+}
+
+// Measure the process group until `finished` fires, killing it if it
+// exceeds a limit.
+fn watch(
+    pgid: libc::pid_t,
+    limits: Limits,
+    start: Instant,
+    finished: mpsc::Receiver<()>,
+) -> Option<Exceeded> {
+    if limits == Limits::default() {
+        let _ = finished.recv();
+        return None;
+    }
+    loop {
+        match finished.recv_timeout(POLL_INTERVAL) {
+            Err(RecvTimeoutError::Timeout) => {}
+            _ => return None,
+        }
+        let usage = group_usage(pgid);
+        let out_of_time = limits.time.map(Duration::from_secs).is_some_and(|limit| {
+            usage.cpu_time > limit || start.elapsed() > limit + WALL_CLOCK_GRACE
+        });
+        let out_of_memory = limits
+            .memory
+            .is_some_and(|limit| usage.rss_bytes > limit * 1024 * 1024);
+        let exceeded = if out_of_time {
+            Some(Exceeded::Time)
+        } else if out_of_memory {
+            Some(Exceeded::Memory)
+        } else {
+            None
+        };
+        if exceeded.is_some() {
+            kill_group(pgid);
+            return exceeded;
+        }
+    }
+}
+
+fn kill_group(pgid: libc::pid_t) {
+    unsafe {
+        libc::kill(-pgid, libc::SIGKILL);
+    }
+}
+
+struct Usage {
+    cpu_time: Duration,
+    rss_bytes: u64,
+}
+
+// Total CPU time and resident memory of the processes in a group,
+// read from /proc.
+fn group_usage(pgid: libc::pid_t) -> Usage {
+    let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) }.max(1) as u64;
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) }.max(1) as u64;
+    let mut usage = Usage {
+        cpu_time: Duration::default(),
+        rss_bytes: 0,
+    };
+    let entries = match fs::read_dir("/proc") {
+        Ok(entries) => entries,
+        Err(_) => return usage,
+    };
+    for entry in entries.flatten() {
+        let stat = match fs::read_to_string(entry.path().join("stat")) {
+            Ok(stat) => stat,
+            Err(_) => continue,
+        };
+        // The command name is in parentheses and may contain spaces,
+        // so count fields from the closing parenthesis.
+        let fields: Vec<&str> = match stat.rfind(')') {
+            Some(i) => stat[i + 1..].split_whitespace().collect(),
+            None => continue,
+        };
+        let field = |n: usize| fields.get(n - 3).and_then(|f| f.parse::<u64>().ok());
+        if field(5) != Some(pgid as u64) {
+            continue;
+        }
+        let cpu_ticks = field(14).unwrap_or(0) + field(15).unwrap_or(0);
+        usage.cpu_time += Duration::from_millis(cpu_ticks * 1000 / ticks);
+        usage.rss_bytes += field(24).unwrap_or(0) * page_size;
+    }
+    usage
+}
+
+// Read a pipe on another thread so that a chatty child cannot block
+// on a full pipe while we wait for it.
+fn drain<R: Read + Send + 'static>(pipe: Option<R>) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buffer);
+        }
+        buffer
+    })
+}
+
+fn wait4(pid: libc::pid_t) -> io::Result<(ExitStatus, u64)> {
+    let mut status = 0;
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    loop {
+        let r = unsafe { libc::wait4(pid, &mut status, 0, &mut usage) };
+        if r == pid {
+            // Linux reports ru_maxrss in kilobytes.
+            return Ok((ExitStatus::from_raw(status), usage.ru_maxrss as u64));
+        }
+        let error = io::Error::last_os_error();
+        if error.kind() != io::ErrorKind::Interrupted {
+            return Err(error);
+        }
+    }
+}
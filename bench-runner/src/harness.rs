@@ -0,0 +1,186 @@
+//! Verification harnesses in user crates, for `cargo verify`.
+//!
+//! A harness is a function marked with `#[verifier::harness]`. Like the
+//! headers of benchmarks, harnesses are found by scanning the sources of
+//! the crate. They are built into the crate's test executables: natively
+//! each is an ordinary test, and with `verifier-klee` each is exported as
+//! an entry point for KLEE.
+
+use crate::backend::{Input, VerifierBackend};
+use crate::compile::{self, Artifact, CompileError, BITCODE_FLAGS};
+use crate::options::BenchmarkOptions;
+use crate::spec;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// The attributes that mark a harness, depending on how it is imported.
+const ATTRIBUTES: &[&str] = &["#[verifier::harness]", "#[harness]"];
+
+/// A function marked with `#[verifier::harness]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Harness {
+    pub name: String,
+    pub path: PathBuf,
+    /// The line of the function's `fn`.
+    pub line: u32,
+    /// The integration test that contains the harness, or `None` if it is
+    /// in the package's library or binary.
+    pub test: Option<String>,
+}
+
+impl Harness {
+    /// The function that `#[verifier::harness]` exports for KLEE.
+    pub fn symbol(&self) -> String {
+        format!("verifier_harness_{}", self.name)
+    }
+
+    /// The full name of the harness's test, such as
+    /// `tests::verifier_harness_foo`, in the `--list` output of its test
+    /// executable. Harnesses are exported by name for KLEE, so the name
+    /// is unique within the executable.
+    pub fn test_name(&self, listing: &str) -> Option<String> {
+        let symbol = self.symbol();
+        listing
+            .lines()
+            .filter_map(|line| line.strip_suffix(": test"))
+            .find(|test| test.rsplit("::").next() == Some(symbol.as_str()))
+            .map(|test| test.to_string())
+    }
+
+    /// The test executable that contains the harness, among the artifacts
+    /// of `build`.
+    pub fn executable<'a>(&self, artifacts: &'a [Artifact]) -> Option<&'a Path> {
+        let tests = artifacts.iter().filter(|artifact| artifact.profile.test);
+        let target = match &self.test {
+            Some(name) => tests
+                .filter(|artifact| artifact.target.kind.iter().any(|kind| kind == "test"))
+                .find(|artifact| &artifact.target.name == name),
+            None => {
+                let mut own: Vec<&Artifact> = tests
+                    .filter(|artifact| {
+                        let kind = &artifact.target.kind;
+                        kind.iter().any(|kind| kind != "test" && kind != "bench")
+                    })
+                    .collect();
+                // A package with a library usually keeps its code there.
+                own.sort_by_key(|artifact| !artifact.target.kind.iter().any(|k| k == "lib"));
+                own.into_iter().next()
+            }
+        };
+        target?.executable.as_deref()
+    }
+}
+
+/// Find the harnesses in the `src` and `tests` directories of the
+/// package in `package_dir`, in a stable order.
+pub fn discover(package_dir: &Path) -> io::Result<Vec<Harness>> {
+    let mut harnesses = Vec::new();
+    for (dir, is_test) in &[("src", false), ("tests", true)] {
+        let dir = package_dir.join(dir);
+        if !dir.is_dir() {
+            continue;
+        }
+        let mut files = Vec::new();
+        spec::walk(&dir, &mut files)?;
+        files.sort();
+        for path in files {
+            // An integration test is a file directly in `tests`, or a
+            // directory there with a `main.rs`.
+            let test = if *is_test {
+                let relative = path.strip_prefix(&dir).unwrap_or(&path);
+                match relative.components().next() {
+                    Some(first) if relative.components().count() > 1 => {
+                        Some(first.as_os_str().to_string_lossy().into_owned())
+                    }
+                    _ => Some(relative.with_extension("").to_string_lossy().into_owned()),
+                }
+            } else {
+                None
+            };
+            let source = fs::read_to_string(&path)?;
+            harnesses.extend(scan(&source).into_iter().map(|(name, line)| Harness {
+                name,
+                path: path.clone(),
+                line,
+                test: test.clone(),
+            }));
+        }
+    }
+    Ok(harnesses)
+}
+
+// The name and line of each function that follows a harness attribute,
+// possibly after other attributes or on the same line.
+fn scan(source: &str) -> Vec<(String, u32)> {
+    let mut harnesses = Vec::new();
+    let mut marked = false;
+    for (number, line) in (1..).zip(source.lines()) {
+        let mut rest = line.trim();
+        for attribute in ATTRIBUTES {
+            if let Some(after) = rest.strip_prefix(attribute) {
+                marked = true;
+                rest = after.trim_start();
+            }
+        }
+        if !marked {
+            continue;
+        }
+        let mut words = rest.split_whitespace();
+        if words.any(|word| word == "fn") {
+            if let Some(word) = words.next() {
+                let end = word
+                    .find(|c: char| !c.is_alphanumeric() && c != '_')
+                    .unwrap_or(word.len());
+                harnesses.push((word[..end].to_string(), number));
+            }
+            marked = false;
+        }
+    }
+    harnesses
+}
+
+/// Build the test executables of the package in `package_dir` for
+/// `backend`, which must take bitcode or executables.
+///
+/// A backend that takes bitcode gets a `.bc` file next to each
+/// executable, which is not itself written.
+pub fn build(
+    package_dir: &Path,
+    target_dir: &Path,
+    backend: &dyn VerifierBackend,
+) -> Result<Vec<Artifact>, CompileError> {
+    let mut rustflags = backend.rustc_flags(&BenchmarkOptions::default());
+    match backend.input() {
+        Input::Executable => {}
+        Input::Bitcode => {
+            rustflags.extend(BITCODE_FLAGS.iter().map(|flag| flag.to_string()));
+            // The test harness unwinds by default, which KLEE cannot.
+            rustflags.push("-Zpanic-abort-tests".to_string());
+        }
+        Input::Source | Input::Package => {
+            return Err(CompileError {
+                output: format!("the {} backend cannot verify harnesses", backend.name()),
+            })
+        }
+    }
+
+    let mut command = Command::new("cargo");
+    command
+        .arg("test")
+        .arg("--no-run")
+        .arg("--manifest-path")
+        .arg(package_dir.join("Cargo.toml"))
+        .env("CARGO_TARGET_DIR", target_dir)
+        .env("RUSTFLAGS", rustflags.join(" "));
+    let features: Vec<String> = backend
+        .features()
+        .iter()
+        .map(|feature| format!("verifier/{}", feature))
+        .collect();
+    if !features.is_empty() {
+        command.arg("--features").arg(features.join(","));
+    }
+    compile::cargo(command)
+}
@@ -0,0 +1,24 @@
+//! Support for running the benchmark suite against a verifier.
+//!
+//! Each benchmark is a single Rust file whose header comments declare
+//! the expected verification result and any verifier flags:
+//!
+//! ```text
+//! // @flag --no-memory-splitting --unroll=4
+//! // @expect verified
+//! ```
+
+pub mod backend;
+pub mod compare;
+pub mod compile;
+pub mod config;
+pub mod cover;
+pub mod exec;
+pub mod harness;
+pub mod options;
+pub mod report;
+pub mod runner;
+pub mod score;
+pub mod spec;
+pub mod svcomp;
+pub mod verdict;
@@ -0,0 +1,389 @@
+use bench_runner::backend::{self, Settings};
+use bench_runner::compare::Comparison;
+use bench_runner::compile::Builder;
+use bench_runner::exec::Limits;
+use bench_runner::report::{Record, Report};
+use bench_runner::runner::Runner;
+use bench_runner::score::Scores;
+use bench_runner::spec::{self, BenchmarkSpec};
+use bench_runner::svcomp;
+use bench_runner::verdict::Outcome;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::thread;
+use std::time::Instant;
+
+const USAGE: &str = "\
+usage: bench-runner [options] [PATH...]
+       bench-runner svcomp-export [--properties DIR] [PATH...]
+       bench-runner svcomp-import TASK.yml...
+       bench-runner compare [--slowdown FACTOR] OLD.json NEW.json
+       bench-runner score REPORT.json...
+
+Runs every benchmark under each PATH (default: benchmarks/smack-regressions)
+and checks the verifier's verdict against the `// @expect` header.
+
+svcomp-export writes an SV-COMP task definition next to each benchmark and
+the property files to DIR (default: benchmarks/properties). svcomp-import
+rewrites the `@expect`/`@flag` header of each task's input file.
+
+compare lists the benchmarks whose verdict changed between two JSON reports,
+or that got more than FACTOR times slower (default: 2), and fails if any
+benchmark expected to fail is newly reported as verified.
+
+score prints the SV-COMP score of each JSON report for every category.
+
+options:
+    --backend NAME       smack, klee, mirai, native or random (default: smack)
+    --verifier PROGRAM   program that the backend invokes instead of its default
+    --crates DIR         where the verifier, symbolic and klee-annotations crates
+                         are (default: the current directory)
+    --work-dir DIR       where built benchmarks are placed (default: target/bench-runner)
+    -j, --jobs N         run N benchmarks at a time (default: number of CPUs)
+    --time-limit SECS    cap on the CPU time of each benchmark
+    --memory-limit MB    cap on the memory of each benchmark
+    --json FILE          write the results as JSON
+    --junit FILE         write the results as JUnit XML
+    -v, --verbose        show verifier output for benchmarks that do not pass
+    -h, --help           show this message";
+
+const DEFAULT_PATH: &str = "benchmarks/smack-regressions";
+
+struct Options {
+    backend: String,
+    settings: Settings,
+    crates_dir: PathBuf,
+    work_dir: PathBuf,
+    jobs: usize,
+    limits: Limits,
+    json: Option<PathBuf>,
+    junit: Option<PathBuf>,
+    verbose: bool,
+    paths: Vec<PathBuf>,
+}
+
+fn parse_args(args: Vec<String>) -> Options {
+    let mut options = Options {
+        backend: "smack".to_string(),
+        settings: Settings::default(),
+        crates_dir: PathBuf::from("."),
+        work_dir: PathBuf::from("target/bench-runner"),
+        jobs: thread::available_parallelism().map_or(1, |n| n.get()),
+        limits: Limits::default(),
+        json: None,
+        junit: None,
+        verbose: false,
+        paths: Vec::new(),
+    };
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().unwrap_or_else(|| usage_error());
+        match arg.as_str() {
+            "--backend" => options.backend = value(),
+            "--verifier" => options.settings.program = Some(value()),
+            "--crates" => options.crates_dir = PathBuf::from(value()),
+            "--work-dir" => options.work_dir = PathBuf::from(value()),
+            "-j" | "--jobs" => options.jobs = number(&value()),
+            "--time-limit" => options.limits.time = Some(number(&value())),
+            "--memory-limit" => options.limits.memory = Some(number(&value())),
+            "--json" => options.json = Some(PathBuf::from(value())),
+            "--junit" => options.junit = Some(PathBuf::from(value())),
+            "-v" | "--verbose" => options.verbose = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0)
+            }
+            _ if arg.starts_with('-') => usage_error(),
+            _ => options.paths.push(PathBuf::from(arg)),
+        }
+    }
+    if options.paths.is_empty() {
+        options.paths.push(PathBuf::from(DEFAULT_PATH));
+    }
+    options
+}
+
+fn number<T: std::str::FromStr>(value: &str) -> T {
+    value.parse().unwrap_or_else(|_| usage_error())
+}
+
+fn cannot_write(path: &Path, e: std::io::Error) -> ! {
+    eprintln!("bench-runner: cannot write {}: {}", path.display(), e);
+    process::exit(2)
+}
+
+fn load_report(path: &Path) -> Report {
+    Report::load(path).unwrap_or_else(|e| {
+        eprintln!("bench-runner: cannot read {}: {}", path.display(), e);
+        process::exit(2)
+    })
+}
+
+fn usage_error() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2)
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("svcomp-export") => svcomp_export(&args[1..]),
+        Some("svcomp-import") => svcomp_import(&args[1..]),
+        Some("compare") => compare(&args[1..]),
+        Some("score") => score(&args[1..]),
+        _ => run(args),
+    }
+}
+
+// Load the specs of all benchmarks under `paths`, reporting any that
+// cannot be parsed.
+fn load_specs(paths: &[PathBuf]) -> (Vec<BenchmarkSpec>, usize) {
+    let files = spec::discover(paths).unwrap_or_else(|e| {
+        eprintln!("bench-runner: {}", e);
+        process::exit(2)
+    });
+    let mut specs = Vec::new();
+    let mut broken = 0;
+    for file in &files {
+        match BenchmarkSpec::load(file) {
+            Ok(spec) => specs.push(spec),
+            Err(e) => {
+                println!("BROKEN      {}: {}", file.display(), e);
+                broken += 1;
+            }
+        }
+    }
+    (specs, broken)
+}
+
+fn svcomp_export(args: &[String]) {
+    let mut properties_dir = PathBuf::from("benchmarks/properties");
+    let mut paths = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--properties" => {
+                properties_dir = PathBuf::from(args.next().unwrap_or_else(|| usage_error()))
+            }
+            _ if arg.starts_with('-') => usage_error(),
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+    if paths.is_empty() {
+        paths.push(PathBuf::from(DEFAULT_PATH));
+    }
+
+    let (specs, broken) = load_specs(&paths);
+    match svcomp::export(&specs, &properties_dir) {
+        Ok(written) => println!("wrote {} task definitions", written.len()),
+        Err(e) => {
+            eprintln!("bench-runner: {}", e);
+            process::exit(2)
+        }
+    }
+    if broken > 0 {
+        process::exit(1)
+    }
+}
+
+fn svcomp_import(args: &[String]) {
+    if args.is_empty() || args.iter().any(|arg| arg.starts_with('-')) {
+        usage_error()
+    }
+    let mut failed = false;
+    for task in args {
+        match svcomp::import(Path::new(task)) {
+            Ok(benchmark) => println!("updated {}", benchmark.display()),
+            Err(e) => {
+                println!("BROKEN      {}: {}", task, e);
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        process::exit(1)
+    }
+}
+
+fn compare(args: &[String]) {
+    let mut slowdown = 2.0;
+    let mut reports = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--slowdown" => slowdown = number(args.next().unwrap_or_else(|| usage_error())),
+            _ if arg.starts_with('-') => usage_error(),
+            _ => reports.push(PathBuf::from(arg)),
+        }
+    }
+    if reports.len() != 2 {
+        usage_error()
+    }
+    let (old, new) = (load_report(&reports[0]), load_report(&reports[1]));
+
+    let comparison = Comparison::new(&old, &new, slowdown);
+    print!("{}", comparison.to_table());
+    let unsound = comparison.soundness_regressions().count();
+    if unsound > 0 {
+        println!();
+        println!("UNSOUND count    {}", unsound);
+        process::exit(1)
+    }
+}
+
+fn score(args: &[String]) {
+    if args.is_empty() || args.iter().any(|arg| arg.starts_with('-')) {
+        usage_error()
+    }
+    let mut columns = Vec::new();
+    for arg in args {
+        let path = Path::new(arg);
+        let report = load_report(path);
+        let name = path.file_stem().map_or(report.backend.clone(), |stem| {
+            stem.to_string_lossy().into_owned()
+        });
+        columns.push((name, Scores::new(&report)));
+    }
+    let mut categories: Vec<&String> = columns
+        .iter()
+        .flat_map(|(_, scores)| scores.categories.keys())
+        .collect();
+    categories.sort_unstable();
+    categories.dedup();
+
+    print!("{:<16}", "CATEGORY");
+    for (name, _) in &columns {
+        print!(" {:>16}", name);
+    }
+    println!();
+    for category in categories {
+        print!("{:<16}", category);
+        for (_, scores) in &columns {
+            match scores.categories.get(category) {
+                Some(s) => print!(" {:>16}", format!("{}/{}", s.points, s.max_points)),
+                None => print!(" {:>16}", "-"),
+            }
+        }
+        println!();
+    }
+    print!("{:<16}", "TOTAL");
+    for (_, scores) in &columns {
+        let s = scores.total;
+        print!(" {:>16}", format!("{}/{}", s.points, s.max_points));
+    }
+    println!();
+    println!();
+    for (name, scores) in &columns {
+        let s = scores.total;
+        println!(
+            "{}: {} correct true, {} correct false, {} wrong true, {} wrong false, {} unknown",
+            name, s.correct_true, s.correct_false, s.wrong_true, s.wrong_false, s.unknown
+        );
+    }
+}
+
+fn run(args: Vec<String>) {
+    let options = parse_args(args);
+    let backend = backend::by_name(&options.backend, &options.settings).unwrap_or_else(|| {
+        eprintln!(
+            "bench-runner: unknown backend '{}' (expected one of {})",
+            options.backend,
+            backend::BACKENDS.join(", ")
+        );
+        process::exit(2)
+    });
+    let runner = Runner {
+        backend,
+        builder: Builder::new(options.crates_dir, options.work_dir),
+        limits: options.limits,
+    };
+
+    let mut report = Report::new(runner.backend.name());
+    let start = Instant::now();
+    let (specs, broken) = load_specs(&options.paths);
+    let (mut passed, mut failed, mut unexpected, mut skipped) = (0, 0, 0, 0);
+    let mut vacuous = 0;
+    let mut runnable = Vec::new();
+    for spec in &specs {
+        if spec.skipped_by(runner.backend.name()) {
+            println!("SKIPPED     {}", spec.path.display());
+            skipped += 1;
+        } else {
+            runnable.push(spec);
+        }
+    }
+
+    let verbose = options.verbose;
+    let ran = runner.run_parallel(&runnable, options.jobs, |result| {
+        match result.outcome {
+            Outcome::Pass => passed += 1,
+            Outcome::Fail => failed += 1,
+            Outcome::Unexpected => unexpected += 1,
+        }
+        let mut got = result.verdict.to_string();
+        if let Some(location) = &result.failure_location {
+            got.push_str(&format!(" at {}", location));
+        }
+        if result.vacuous {
+            vacuous += 1;
+            got.push_str(" (vacuous)");
+        }
+        if result.verdict.satisfies(result.expect) {
+            match result.outcome {
+                Outcome::Fail => {
+                    got.push_str(" not at the expected line or without the expected message")
+                }
+                Outcome::Unexpected => got.push_str(" without a location"),
+                Outcome::Pass => {}
+            }
+        }
+        println!(
+            "{:<11} {}  [{:.2}s] expected {}, got {}",
+            result.outcome.to_string(),
+            result.path.display(),
+            result.elapsed.as_secs_f64(),
+            result.expect,
+            got
+        );
+        for point in &result.uncovered {
+            println!("    not covered: {} (line {})", point.label, point.line);
+        }
+        if verbose && result.outcome != Outcome::Pass {
+            for line in result.output.lines() {
+                println!("    {}", line);
+            }
+        }
+        report.results.push(Record::new(&result));
+    });
+    if let Err(e) = ran {
+        eprintln!("bench-runner: cannot run {}: {}", runner.backend.name(), e);
+        process::exit(2)
+    }
+    report.results.sort_by(|a, b| a.file.cmp(&b.file));
+
+    if let Some(path) = &options.json {
+        report
+            .write_json(path)
+            .unwrap_or_else(|e| cannot_write(path, e));
+    }
+    if let Some(path) = &options.junit {
+        report
+            .write_junit(path)
+            .unwrap_or_else(|e| cannot_write(path, e));
+    }
+
+    println!();
+    println!("ELAPSED TIME     [{:.2}s]", start.elapsed().as_secs_f64());
+    println!("PASSED count     {}", passed);
+    println!("FAILED count     {}", failed);
+    println!("UNEXPECTED count {}", unexpected);
+    println!("SKIPPED count    {}", skipped);
+    println!("BROKEN count     {}", broken);
+    println!("VACUOUS count    {}", vacuous);
+    let score = Scores::new(&report).total;
+    println!("SCORE            {} of {}", score.points, score.max_points);
+
+    if failed > 0 || unexpected > 0 || broken > 0 {
+        process::exit(1)
+    }
+}
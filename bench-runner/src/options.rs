@@ -0,0 +1,157 @@
+//! The verifier-neutral options that a benchmark can set with `// @flag`.
+//!
+//! The spelling of the flags follows SMACK, which the benchmarks were
+//! first written for, but each backend maps them onto its own CLI.
+
+use crate::spec::SpecError;
+
+/// How the verifier should model memory.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MemoryModel {
+    /// Whatever the verifier does by default.
+    #[default]
+    Default,
+    /// Treat memory as a single region (`--no-memory-splitting`).
+    NoSplitting,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BenchmarkOptions {
+    /// Number of times to unroll loops and recursion (`--unroll=N`).
+    pub loop_bound: Option<u32>,
+    /// Model integers as bitvectors instead of mathematical integers
+    /// (`--bit-precise`).
+    pub bit_precise: bool,
+    /// Report arithmetic overflow as an error (`--integer-overflow`).
+    pub integer_overflow: bool,
+    pub memory_model: MemoryModel,
+    /// Give up after this many seconds (`--timeout=N`).
+    pub timeout: Option<u64>,
+}
+
+impl BenchmarkOptions {
+    /// Parse the arguments of all `// @flag` lines.
+    pub fn parse<'a, I>(flags: I) -> Result<BenchmarkOptions, SpecError>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let mut options = BenchmarkOptions::default();
+        for flag in flags {
+            let (name, value) = match flag.find('=') {
+                Some(i) => (&flag[..i], Some(&flag[i + 1..])),
+                None => (flag, None),
+            };
+            match (name, value) {
+                ("--unroll", Some(n)) => options.loop_bound = Some(number(flag, n)?),
+                ("--timeout", Some(n)) => options.timeout = Some(number(flag, n)?),
+                ("--bit-precise", None) => options.bit_precise = true,
+                ("--integer-overflow", None) => options.integer_overflow = true,
+                ("--no-memory-splitting", None) => options.memory_model = MemoryModel::NoSplitting,
+                ("--unroll", None) | ("--timeout", None) => {
+                    return Err(SpecError::InvalidFlag(flag.to_string(), "expected a value"))
+                }
+                ("--bit-precise", Some(_))
+                | ("--integer-overflow", Some(_))
+                | ("--no-memory-splitting", Some(_)) => {
+                    return Err(SpecError::InvalidFlag(flag.to_string(), "takes no value"))
+                }
+                _ => return Err(SpecError::UnknownFlag(flag.to_string())),
+            }
+        }
+        Ok(options)
+    }
+
+    /// The `// @flag` arguments that `parse` would turn back into `self`.
+    pub fn to_flags(&self) -> Vec<String> {
+        let mut flags = Vec::new();
+        if self.memory_model == MemoryModel::NoSplitting {
+            flags.push("--no-memory-splitting".to_string());
+        }
+        if let Some(n) = self.loop_bound {
+            flags.push(format!("--unroll={}", n));
+        }
+        if self.bit_precise {
+            flags.push("--bit-precise".to_string());
+        }
+        if self.integer_overflow {
+            flags.push("--integer-overflow".to_string());
+        }
+        if let Some(n) = self.timeout {
+            flags.push(format!("--timeout={}", n));
+        }
+        flags
+    }
+}
+
+fn number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, SpecError> {
+    value
+        .parse()
+        .map_err(|_| SpecError::InvalidFlag(flag.to_string(), "expected a number"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_flags() {
+        let options = BenchmarkOptions::parse(vec![
+            "--unroll=4",
+            "--bit-precise",
+            "--no-memory-splitting",
+            "--timeout=30",
+        ])
+        .unwrap();
+        assert_eq!(
+            options,
+            BenchmarkOptions {
+                loop_bound: Some(4),
+                bit_precise: true,
+                integer_overflow: false,
+                memory_model: MemoryModel::NoSplitting,
+                timeout: Some(30),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(matches!(
+            BenchmarkOptions::parse(vec!["--unroll"]),
+            Err(SpecError::InvalidFlag(..))
+        ));
+        assert!(matches!(
+            BenchmarkOptions::parse(vec!["--unroll=four"]),
+            Err(SpecError::InvalidFlag(..))
+        ));
+        assert!(matches!(
+            BenchmarkOptions::parse(vec!["--bit-precise=1"]),
+            Err(SpecError::InvalidFlag(..))
+        ));
+        assert!(matches!(
+            BenchmarkOptions::parse(vec!["--loop-limit=3"]),
+            Err(SpecError::UnknownFlag(_))
+        ));
+    }
+
+    #[test]
+    fn to_flags_round_trips() {
+        let options = BenchmarkOptions {
+            loop_bound: Some(2),
+            bit_precise: false,
+            integer_overflow: true,
+            memory_model: MemoryModel::NoSplitting,
+            timeout: None,
+        };
+        let flags = options.to_flags();
+        assert_eq!(
+            flags,
+            ["--no-memory-splitting", "--unroll=2", "--integer-overflow"]
+        );
+        assert_eq!(
+            BenchmarkOptions::parse(flags.iter().map(String::as_str)).unwrap(),
+            options
+        );
+        assert!(BenchmarkOptions::default().to_flags().is_empty());
+    }
+}
@@ -0,0 +1,211 @@
+//! Machine-readable reports of a benchmark run.
+//!
+//! The JSON report can be read back (for example to compare two runs),
+//! and the JUnit XML report is understood by most CI test reporters.
+
+use crate::runner::RunResult;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The results of running a set of benchmarks with one backend.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Report {
+    pub backend: String,
+    pub results: Vec<Record>,
+}
+
+/// One benchmark's result.
+///
+/// Verdicts and expectations are recorded as the strings used in
+/// benchmark headers so that reports stay readable across versions.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Record {
+    pub file: String,
+    /// The directory containing the benchmark, such as `basic` or `loops`.
+    pub category: String,
+    pub backend: String,
+    pub expected: String,
+    pub verdict: String,
+    /// `PASSED`, `FAILED` or `UNEXPECTED`.
+    pub outcome: String,
+    /// Wall-clock time in seconds.
+    pub time: f64,
+    /// Peak memory in kilobytes.
+    pub memory_kb: Option<u64>,
+    pub exit_code: Option<i32>,
+    /// The labels of the cover points that a verified run did not cover.
+    #[serde(default)]
+    pub uncovered: Vec<String>,
+    /// Whether the benchmark may only have verified vacuously.
+    #[serde(default)]
+    pub vacuous: bool,
+    /// Everything the verifier printed.
+    pub diagnostics: String,
+}
+
+impl Record {
+    pub fn new(result: &RunResult) -> Record {
+        Record {
+            file: result.path.to_string_lossy().into_owned(),
+            category: category(&result.path),
+            backend: result.backend.to_string(),
+            expected: result.expect.to_string(),
+            verdict: result.verdict.to_string(),
+            outcome: result.outcome.to_string(),
+            time: result.elapsed.as_secs_f64(),
+            memory_kb: result.max_rss_kb,
+            exit_code: result.exit_code,
+            uncovered: result
+                .uncovered
+                .iter()
+                .map(|point| point.label.clone())
+                .collect(),
+            vacuous: result.vacuous,
+            diagnostics: result.output.clone(),
+        }
+    }
+
+    /// The file name of the benchmark without its extension.
+    pub fn name(&self) -> &str {
+        Path::new(&self.file)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or(&self.file)
+    }
+}
+
+fn category(path: &Path) -> String {
+    path.parent()
+        .and_then(|dir| dir.file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+impl Report {
+    pub fn new(backend: &str) -> Report {
+        Report {
+            backend: backend.to_string(),
+            results: Vec::new(),
+        }
+    }
+
+    pub fn load(path: &Path) -> io::Result<Report> {
+        let text = fs::read_to_string(path)?;
+        serde_json::from_str(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn write_json(&self, path: &Path) -> io::Result<()> {
+        let text = serde_json::to_string_pretty(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(path, text + "\n")
+    }
+
+    /// One `<testsuite>` per category and one `<testcase>` per benchmark.
+    /// Benchmarks with a wrong verdict are failures and those without a
+    /// definite verdict are errors.
+    pub fn to_junit(&self) -> String {
+        let mut categories: Vec<&str> = self.results.iter().map(|r| r.category.as_str()).collect();
+        categories.sort_unstable();
+        categories.dedup();
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let (failures, errors) = count_problems(self.results.iter());
+        let time: f64 = self.results.iter().map(|r| r.time).sum();
+        let _ = writeln!(
+            xml,
+            "<testsuites name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">",
+            escape(&self.backend),
+            self.results.len(),
+            failures,
+            errors,
+            time
+        );
+        for category in categories {
+            let results: Vec<&Record> = self
+                .results
+                .iter()
+                .filter(|r| r.category == category)
+                .collect();
+            let (failures, errors) = count_problems(results.iter().copied());
+            let time: f64 = results.iter().map(|r| r.time).sum();
+            let _ = writeln!(
+                xml,
+                "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">",
+                escape(category),
+                results.len(),
+                failures,
+                errors,
+                time
+            );
+            for r in results {
+                let _ = write!(
+                    xml,
+                    "    <testcase name=\"{}\" classname=\"{}.{}\" file=\"{}\" time=\"{:.3}\"",
+                    escape(r.name()),
+                    escape(&r.backend),
+                    escape(category),
+                    escape(&r.file),
+                    r.time
+                );
+                let element = match r.outcome.as_str() {
+                    "FAILED" => "failure",
+                    "UNEXPECTED" => "error",
+                    _ => {
+                        xml.push_str("/>\n");
+                        continue;
+                    }
+                };
+                let _ = writeln!(
+                    xml,
+                    ">\n      <{} message=\"expected {}, got {}\" type=\"{}\">{}</{}>\n    </testcase>",
+                    element,
+                    escape(&r.expected),
+                    escape(&r.verdict),
+                    escape(&r.verdict),
+                    escape(&r.diagnostics),
+                    element
+                );
+            }
+            xml.push_str("  </testsuite>\n");
+        }
+        xml.push_str("</testsuites>\n");
+        xml
+    }
+
+    pub fn write_junit(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_junit())
+    }
+}
+
+fn count_problems<'a>(results: impl Iterator<Item = &'a Record>) -> (usize, usize) {
+    let mut failures = 0;
+    let mut errors = 0;
+    for r in results {
+        match r.outcome.as_str() {
+            "FAILED" => failures += 1,
+            "UNEXPECTED" => errors += 1,
+            _ => {}
+        }
+    }
+    (failures, errors)
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters other than whitespace are not allowed in XML.
+            c if c.is_control() && !c.is_whitespace() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
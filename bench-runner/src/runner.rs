@@ -0,0 +1,201 @@
+use crate::backend::{combined_output, Location, VerifierBackend};
+use crate::compile::Builder;
+use crate::cover::{self, CoverPoint};
+use crate::exec::{self, Exceeded, Limits};
+use crate::spec::{BenchmarkSpec, Expectation};
+use crate::verdict::{Outcome, Verdict};
+use std::io;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+/// Runs benchmarks with one verifier backend.
+pub struct Runner {
+    pub backend: Box<dyn VerifierBackend>,
+    /// Builds the benchmarks for the backend.
+    pub builder: Builder,
+    /// Caps on the limits that benchmarks set for themselves.
+    pub limits: Limits,
+}
+
+/// The result of running one benchmark.
+#[derive(Debug)]
+pub struct RunResult {
+    pub path: PathBuf,
+    pub backend: &'static str,
+    pub expect: Expectation,
+    pub verdict: Verdict,
+    pub outcome: Outcome,
+    /// Where the verifier reported a failure, if it did.
+    pub failure_location: Option<Location>,
+    /// The cover points that were not covered in a verified run of a
+    /// backend that reports coverage.
+    pub uncovered: Vec<CoverPoint>,
+    /// Whether a verified run of a backend that reports coverage may be
+    /// vacuous: no run returned from `main` or some cover point was not
+    /// covered, so the assumptions may rule out the runs that matter.
+    pub vacuous: bool,
+    /// Wall-clock time taken by the verifier.
+    pub elapsed: Duration,
+    /// Peak memory use of the verifier in kilobytes.
+    pub max_rss_kb: Option<u64>,
+    /// `None` if the verifier was killed by a signal or never ran.
+    pub exit_code: Option<i32>,
+    /// Combined stdout and stderr of the verifier, or the compiler
+    /// output if the benchmark could not be built.
+    pub output: String,
+}
+
+impl Runner {
+    pub fn run(&self, spec: &BenchmarkSpec) -> io::Result<RunResult> {
+        let input = match self.builder.prepare(spec, &*self.backend) {
+            Ok(input) => input,
+            Err(e) => {
+                return Ok(RunResult {
+                    path: spec.path.clone(),
+                    backend: self.backend.name(),
+                    expect: spec.expect,
+                    verdict: Verdict::Unknown,
+                    outcome: Outcome::judge(Verdict::Unknown, spec.expect),
+                    failure_location: None,
+                    uncovered: Vec::new(),
+                    vacuous: false,
+                    elapsed: Duration::default(),
+                    max_rss_kb: None,
+                    exit_code: None,
+                    output: e.to_string(),
+                })
+            }
+        };
+
+        let flags = self.backend.translate_flags(&spec.options);
+        let limits = spec.limits.min(self.limits);
+        let execution = exec::execute(self.backend.command(&input, &flags), limits)?;
+        let verdict = match execution.exceeded {
+            Some(Exceeded::Time) => Verdict::Timeout,
+            Some(Exceeded::Memory) => Verdict::OutOfMemory,
+            None => self.backend.parse_output(&execution.output),
+        };
+        let output = combined_output(&execution.output);
+        let failure_location = if verdict.is_failure() {
+            self.backend.failure_location(&execution.output)
+        } else {
+            None
+        };
+        let (uncovered, vacuous) =
+            if verdict == Verdict::Verified && self.backend.reports_coverage() {
+                let covered = cover::covered(&output);
+                let uncovered: Vec<CoverPoint> = spec
+                    .cover_points
+                    .iter()
+                    .filter(|point| !covered.contains(&point.label))
+                    .cloned()
+                    .collect();
+                let vacuous = !covered.contains(cover::END_OF_MAIN) || !uncovered.is_empty();
+                (uncovered, vacuous)
+            } else {
+                (Vec::new(), false)
+            };
+        Ok(RunResult {
+            path: spec.path.clone(),
+            backend: self.backend.name(),
+            expect: spec.expect,
+            verdict,
+            outcome: judge(
+                spec,
+                verdict,
+                &output,
+                self.backend.reports_locations(),
+                failure_location.as_ref(),
+            ),
+            failure_location,
+            uncovered,
+            vacuous,
+            elapsed: execution.elapsed,
+            max_rss_kb: Some(execution.max_rss_kb),
+            exit_code: execution.output.status.code(),
+            output,
+        })
+    }
+
+    /// Run `specs` on `jobs` worker threads, passing each result to
+    /// `report` on the calling thread as soon as it is available.
+    ///
+    /// Stops at the first benchmark whose verifier cannot be started.
+    pub fn run_parallel<F>(
+        &self,
+        specs: &[&BenchmarkSpec],
+        jobs: usize,
+        mut report: F,
+    ) -> io::Result<()>
+    where
+        F: FnMut(RunResult),
+    {
+        let next = AtomicUsize::new(0);
+        let (sender, results) = mpsc::channel();
+        thread::scope(|scope| {
+            for _ in 0..jobs.max(1) {
+                let sender = sender.clone();
+                let next = &next;
+                scope.spawn(move || loop {
+                    let i = next.fetch_add(1, Ordering::SeqCst);
+                    if i >= specs.len() {
+                        break;
+                    }
+                    let result = self.run(specs[i]);
+                    let failed = result.is_err();
+                    if sender.send(result).is_err() || failed {
+                        // Make the other workers stop too.
+                        next.store(specs.len(), Ordering::SeqCst);
+                        break;
+                    }
+                });
+            }
+            drop(sender);
+            for result in results {
+                report(result?);
+            }
+            Ok(())
+        })
+    }
+}
+
+// A failure of the expected kind must also have the message and be at
+// the line that the benchmark gives, if the verifier locates failures.
+// A failure that such a verifier does not locate cannot be checked
+// against a line, so it is unexpected.
+fn judge(
+    spec: &BenchmarkSpec,
+    verdict: Verdict,
+    output: &str,
+    locates: bool,
+    location: Option<&Location>,
+) -> Outcome {
+    let outcome = Outcome::judge(verdict, spec.expect);
+    if outcome != Outcome::Pass || !verdict.is_failure() {
+        return outcome;
+    }
+    let message_matches = spec
+        .expect_message
+        .as_ref()
+        .is_none_or(|message| output.contains(message.as_str()));
+    if !message_matches {
+        return Outcome::Fail;
+    }
+    match (spec.failure_line(), location) {
+        (None, _) => Outcome::Pass,
+        (Some(_), _) if !locates => Outcome::Pass,
+        (Some(_), None) => Outcome::Unexpected,
+        // Compare file names only, since the verifier may report the
+        // path of a copy of the benchmark.
+        (Some(line), Some(location)) => {
+            if location.line == line && location.file.file_name() == spec.path.file_name() {
+                Outcome::Pass
+            } else {
+                Outcome::Fail
+            }
+        }
+    }
+}
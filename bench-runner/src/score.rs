@@ -0,0 +1,112 @@
+//! Scoring results with the SV-COMP scheme.
+//!
+//! SV-COMP rewards correct answers and penalises wrong ones heavily, and
+//! a wrong "verified" most heavily of all, because an unsound verifier is
+//! worse than one that gives up. Scoring a report this way gives a single
+//! number per category that can be compared across backends.
+
+use crate::report::{Record, Report};
+use std::collections::BTreeMap;
+
+/// How a result counts towards the score.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Classification {
+    /// Verified a benchmark that is expected to verify.
+    CorrectTrue,
+    /// Found the expected failure.
+    CorrectFalse,
+    /// Verified a benchmark that is expected to fail.
+    WrongTrue,
+    /// Reported a failure that the benchmark does not expect, including
+    /// a different kind of failure from the one expected.
+    WrongFalse,
+    /// Gave no answer: timed out, ran out of memory or printed something
+    /// unrecognised.
+    Unknown,
+}
+
+impl Classification {
+    /// How `record` counts, or `None` if it expects `unknown`: like
+    /// SV-COMP tasks without an expected verdict, such benchmarks are
+    /// not scored.
+    pub fn of(record: &Record) -> Option<Classification> {
+        if record.expected == "unknown" {
+            return None;
+        }
+        Some(match (record.verdict.as_str(), record.outcome.as_str()) {
+            ("verified", "PASSED") => Classification::CorrectTrue,
+            (_, "PASSED") => Classification::CorrectFalse,
+            (_, "UNEXPECTED") => Classification::Unknown,
+            ("verified", _) => Classification::WrongTrue,
+            _ => Classification::WrongFalse,
+        })
+    }
+
+    /// The points SV-COMP awards for this kind of result.
+    pub fn points(self) -> i64 {
+        match self {
+            Classification::CorrectTrue => 2,
+            Classification::CorrectFalse => 1,
+            Classification::WrongTrue => -32,
+            Classification::WrongFalse => -16,
+            Classification::Unknown => 0,
+        }
+    }
+}
+
+/// The score of a set of results.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Score {
+    pub correct_true: usize,
+    pub correct_false: usize,
+    pub wrong_true: usize,
+    pub wrong_false: usize,
+    pub unknown: usize,
+    pub points: i64,
+    /// The points if every result were correct.
+    pub max_points: i64,
+}
+
+impl Score {
+    pub fn add(&mut self, record: &Record) {
+        let classification = match Classification::of(record) {
+            Some(classification) => classification,
+            None => return,
+        };
+        match classification {
+            Classification::CorrectTrue => self.correct_true += 1,
+            Classification::CorrectFalse => self.correct_false += 1,
+            Classification::WrongTrue => self.wrong_true += 1,
+            Classification::WrongFalse => self.wrong_false += 1,
+            Classification::Unknown => self.unknown += 1,
+        }
+        self.points += classification.points();
+        self.max_points += if record.expected == "verified" {
+            Classification::CorrectTrue.points()
+        } else {
+            Classification::CorrectFalse.points()
+        };
+    }
+}
+
+/// The scores of one report, per category and in total.
+#[derive(Debug, Default)]
+pub struct Scores {
+    pub categories: BTreeMap<String, Score>,
+    pub total: Score,
+}
+
+impl Scores {
+    pub fn new(report: &Report) -> Scores {
+        let mut scores = Scores::default();
+        for record in &report.results {
+            scores
+                .categories
+                .entry(record.category.clone())
+                .or_default()
+                .add(record);
+            scores.total.add(record);
+        }
+        scores
+    }
+}
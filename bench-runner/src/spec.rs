@@ -0,0 +1,530 @@
+use crate::backend::BACKENDS;
+use crate::config::DirectoryConfig;
+use crate::cover::{self, CoverPoint};
+use crate::exec::Limits;
+use crate::options::BenchmarkOptions;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// The result that a benchmark declares with `// @expect ...`.
+///
+/// The kinds of failure follow the ways a Rust program can go wrong;
+/// `error` accepts any of them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Expectation {
+    Verified,
+    Error,
+    Overflow,
+    /// `verifier::unreachable!` or `unreachable!` is reached.
+    Reachable,
+    /// An `assert!` or `verifier::assert!` family macro fails.
+    Assertion,
+    /// Any panic, usually combined with a message pattern.
+    Panic,
+    IndexOutOfBounds,
+    DivisionByZero,
+    /// A `RefCell` is borrowed while it is mutably borrowed, or the
+    /// other way round.
+    BorrowError,
+    /// `unwrap` or `expect` is called on `None`.
+    UnwrapNone,
+    /// An invalid pointer dereference or free in `unsafe` code.
+    MemorySafety,
+    /// The verifier is not expected to reach a verdict.
+    Unknown,
+}
+
+impl Expectation {
+    /// Whether the benchmark is expected to fail in some way.
+    pub fn is_failure(self) -> bool {
+        !matches!(self, Expectation::Verified | Expectation::Unknown)
+    }
+}
+
+impl FromStr for Expectation {
+    type Err = SpecError;
+
+    fn from_str(s: &str) -> Result<Self, SpecError> {
+        match s {
+            "verified" => Ok(Expectation::Verified),
+            "error" => Ok(Expectation::Error),
+            "overflow" => Ok(Expectation::Overflow),
+            "reachable" => Ok(Expectation::Reachable),
+            "assertion" => Ok(Expectation::Assertion),
+            "panic" => Ok(Expectation::Panic),
+            "index-out-of-bounds" => Ok(Expectation::IndexOutOfBounds),
+            "division-by-zero" => Ok(Expectation::DivisionByZero),
+            "borrow-error" => Ok(Expectation::BorrowError),
+            "unwrap-none" => Ok(Expectation::UnwrapNone),
+            "memory-safety" => Ok(Expectation::MemorySafety),
+            "unknown" => Ok(Expectation::Unknown),
+            _ => Err(SpecError::UnknownExpectation(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Expectation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Expectation::Verified => "verified",
+            Expectation::Error => "error",
+            Expectation::Overflow => "overflow",
+            Expectation::Reachable => "reachable",
+            Expectation::Assertion => "assertion",
+            Expectation::Panic => "panic",
+            Expectation::IndexOutOfBounds => "index-out-of-bounds",
+            Expectation::DivisionByZero => "division-by-zero",
+            Expectation::BorrowError => "borrow-error",
+            Expectation::UnwrapNone => "unwrap-none",
+            Expectation::MemorySafety => "memory-safety",
+            Expectation::Unknown => "unknown",
+        };
+        f.write_str(s)
+    }
+}
+
+/// Everything the header comments of a benchmark file say about it.
+#[derive(Clone, Debug)]
+pub struct BenchmarkSpec {
+    pub path: PathBuf,
+    pub expect: Expectation,
+    /// Text that the verifier's output must contain when the benchmark
+    /// fails, from `@expect KIND message "TEXT"`.
+    pub expect_message: Option<String>,
+    /// The line that the failure must be reported at, from
+    /// `@expect KIND line N`.
+    pub expect_line: Option<u32>,
+    /// The line with a `// @fails-here` comment, which is another way of
+    /// giving `expect_line`.
+    pub fails_here: Option<u32>,
+    /// Parsed from the arguments of every `// @flag` line.
+    pub options: BenchmarkOptions,
+    /// Set by `// @skip`.
+    pub skip: bool,
+    /// The backends named by `// @skip BACKEND...`, which cannot run the
+    /// benchmark.
+    pub skip_backends: Vec<String>,
+    /// Set by `// @time-limit SECONDS` and `// @memory-limit MEGABYTES`.
+    pub limits: Limits,
+    /// The `verifier::cover!` points in the benchmark.
+    pub cover_points: Vec<CoverPoint>,
+}
+
+#[derive(Debug)]
+pub enum SpecError {
+    Io(io::Error),
+    MissingExpectation,
+    UnknownExpectation(String),
+    InvalidExpectation(String, &'static str),
+    InvalidMarker(&'static str),
+    UnknownFlag(String),
+    InvalidFlag(String, &'static str),
+    InvalidLimit(String),
+    UnknownBackend(String),
+    Config(PathBuf, String),
+}
+
+impl fmt::Display for SpecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SpecError::Io(e) => write!(f, "{}", e),
+            SpecError::MissingExpectation => write!(f, "missing `// @expect` header"),
+            SpecError::UnknownExpectation(s) => write!(f, "unknown `@expect` value '{}'", s),
+            SpecError::InvalidExpectation(s, why) => {
+                write!(f, "invalid `@expect` '{}': {}", s, why)
+            }
+            SpecError::InvalidMarker(why) => write!(f, "{}", why),
+            SpecError::UnknownFlag(s) => write!(f, "unknown `@flag` '{}'", s),
+            SpecError::InvalidFlag(s, why) => write!(f, "invalid `@flag` '{}': {}", s, why),
+            SpecError::InvalidLimit(s) => write!(f, "invalid limit '{}'", s),
+            SpecError::UnknownBackend(s) => write!(f, "unknown backend '{}' in `@skip`", s),
+            SpecError::Config(path, e) => write!(f, "{}: {}", path.display(), e),
+        }
+    }
+}
+
+impl std::error::Error for SpecError {}
+
+impl From<io::Error> for SpecError {
+    fn from(e: io::Error) -> Self {
+        SpecError::Io(e)
+    }
+}
+
+impl BenchmarkSpec {
+    /// The arguments of the benchmark's `@expect` header.
+    pub fn expect_clause(&self) -> String {
+        let mut clause = self.expect.to_string();
+        if let Some(message) = &self.expect_message {
+            clause.push_str(&format!(" message {}", quote(message)));
+        }
+        if let Some(line) = self.expect_line {
+            clause.push_str(&format!(" line {}", line));
+        }
+        clause
+    }
+
+    /// Whether the benchmark is not to be run with `backend`.
+    pub fn skipped_by(&self, backend: &str) -> bool {
+        self.skip || self.skip_backends.iter().any(|name| name == backend)
+    }
+
+    /// The line that the failure must be reported at, if any.
+    pub fn failure_line(&self) -> Option<u32> {
+        self.expect_line.or(self.fails_here)
+    }
+
+    /// Replace the expectation with one given as the arguments of an
+    /// `@expect` header.
+    pub fn set_expect(&mut self, clause: &str) -> Result<(), SpecError> {
+        let (expect, message, line) = expect_clause(clause)?;
+        self.expect = expect;
+        self.expect_message = message;
+        self.expect_line = line;
+        Ok(())
+    }
+
+    /// Read a benchmark, applying the `config.yml` files above it.
+    pub fn load(path: &Path) -> Result<BenchmarkSpec, SpecError> {
+        let source = fs::read_to_string(path)?;
+        let config = DirectoryConfig::for_benchmark(path)?;
+        BenchmarkSpec::parse(path, &source, config)
+    }
+
+    /// Parse the `// @...` header comments of a benchmark, which
+    /// override the directory configuration `config`.
+    ///
+    /// Like regtest.py, annotations are recognised on any line so that
+    /// they can follow a licence header or doc comment.
+    pub fn parse(
+        path: &Path,
+        source: &str,
+        config: DirectoryConfig,
+    ) -> Result<BenchmarkSpec, SpecError> {
+        let mut expect = None;
+        let mut flags: Vec<&str> = config.flags.iter().map(String::as_str).collect();
+        let mut skip = config.skip.unwrap_or(false);
+        let mut skip_backends = Vec::new();
+        let mut limits = Limits {
+            time: config.time_limit,
+            memory: config.memory_limit,
+        };
+
+        let mut fails_here = None;
+        for (number, line) in (1..).zip(source.lines()) {
+            // The marker follows the statement that should fail.
+            if is_fails_here(line)? {
+                if fails_here.is_some() {
+                    return Err(SpecError::InvalidMarker("more than one `@fails-here`"));
+                }
+                fails_here = Some(number);
+            }
+            let annotation = match line.trim_start().strip_prefix("//") {
+                Some(comment) => comment.trim(),
+                None => continue,
+            };
+            if let Some(value) = annotation.strip_prefix("@expect ") {
+                expect = Some(expect_clause(value.trim())?);
+            } else if let Some(value) = annotation.strip_prefix("@flag ") {
+                flags.extend(value.split_whitespace());
+            } else if annotation == "@skip" {
+                skip = true;
+            } else if let Some(value) = annotation.strip_prefix("@skip ") {
+                for name in value.split_whitespace() {
+                    if !BACKENDS.contains(&name) {
+                        return Err(SpecError::UnknownBackend(name.to_string()));
+                    }
+                    skip_backends.push(name.to_string());
+                }
+            } else if let Some(value) = annotation.strip_prefix("@time-limit ") {
+                limits.time = Some(limit(value)?);
+            } else if let Some(value) = annotation.strip_prefix("@memory-limit ") {
+                limits.memory = Some(limit(value)?);
+            }
+        }
+
+        let (expect, expect_message, expect_line) = expect.ok_or(SpecError::MissingExpectation)?;
+        if fails_here.is_some() {
+            if !expect.is_failure() {
+                return Err(SpecError::InvalidMarker(
+                    "`@fails-here` in a benchmark that should not fail",
+                ));
+            }
+            if expect_line.is_some() {
+                return Err(SpecError::InvalidMarker(
+                    "`@fails-here` and `@expect ... line` together",
+                ));
+            }
+        }
+        Ok(BenchmarkSpec {
+            path: path.to_path_buf(),
+            expect,
+            expect_message,
+            expect_line,
+            fails_here,
+            options: BenchmarkOptions::parse(flags)?,
+            skip,
+            skip_backends,
+            limits,
+            cover_points: cover::points(source),
+        })
+    }
+}
+
+// Parse `KIND [message "TEXT"] [line N]`.
+fn expect_clause(clause: &str) -> Result<(Expectation, Option<String>, Option<u32>), SpecError> {
+    let invalid = |why| SpecError::InvalidExpectation(clause.to_string(), why);
+    let (kind, mut rest) = match clause.find(char::is_whitespace) {
+        Some(i) => (&clause[..i], clause[i..].trim_start()),
+        None => (clause, ""),
+    };
+    let kind: Expectation = kind.parse()?;
+    let mut message = None;
+    let mut line = None;
+    while !rest.is_empty() {
+        if let Some(quoted) = rest.strip_prefix("message ") {
+            let (text, after) = quoted_string(quoted.trim_start())
+                .ok_or_else(|| invalid("expected a quoted message"))?;
+            message = Some(text);
+            rest = after;
+        } else if let Some(number) = rest.strip_prefix("line ") {
+            let number = number.trim_start();
+            let end = number.find(char::is_whitespace).unwrap_or(number.len());
+            line = Some(
+                number[..end]
+                    .parse()
+                    .map_err(|_| invalid("expected a line number"))?,
+            );
+            rest = &number[end..];
+        } else {
+            return Err(invalid("expected `message` or `line`"));
+        }
+        rest = rest.trim_start();
+    }
+    if !kind.is_failure() && (message.is_some() || line.is_some()) {
+        return Err(invalid("only failures have a message or line"));
+    }
+    Ok((kind, message, line))
+}
+
+// Split a double-quoted string, in which `\"` and `\\` are escapes, from
+// the text after it.
+pub(crate) fn quoted_string(text: &str) -> Option<(String, &str)> {
+    let mut chars = text.strip_prefix('"')?.char_indices();
+    let mut unquoted = String::new();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Some((unquoted, &text[i + 2..])),
+            '\\' => unquoted.push(chars.next()?.1),
+            c => unquoted.push(c),
+        }
+    }
+    None
+}
+
+// Quote `text` so that `quoted_string` reads it back.
+pub(crate) fn quote(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        if c == '"' || c == '\\' {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+// Whether `line` is code followed by a `// @fails-here` comment, which
+// may go on to say why, as in `// @fails-here: index out of bounds`.
+// Any other mention of the marker in a trailing comment is an error.
+fn is_fails_here(line: &str) -> Result<bool, SpecError> {
+    let start = match comment_start(line) {
+        Some(start) if !line[..start].trim().is_empty() => start,
+        _ => return Ok(false),
+    };
+    let comment = &line[start..];
+    if let Some(rest) = comment.strip_prefix("// @fails-here") {
+        if rest.is_empty() || rest.starts_with(':') || rest.starts_with(char::is_whitespace) {
+            return Ok(true);
+        }
+    }
+    if comment.contains("@fails-here") {
+        return Err(SpecError::InvalidMarker(
+            "`@fails-here` must start a trailing `// @fails-here` comment",
+        ));
+    }
+    Ok(false)
+}
+
+// Where the `//` that starts the comment on `line` is, skipping string
+// and character literals.
+fn comment_start(line: &str) -> Option<usize> {
+    let bytes = line.as_bytes();
+    let mut in_string = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if in_string => i += 1,
+            b'"' => in_string = !in_string,
+            // A character literal such as '"' or '\'', not a lifetime.
+            b'\'' if !in_string => {
+                if bytes.get(i + 1) == Some(&b'\\') {
+                    i += 3;
+                    while i < bytes.len() && bytes[i] != b'\'' {
+                        i += 1;
+                    }
+                } else if bytes.get(i + 2) == Some(&b'\'') {
+                    i += 2;
+                }
+            }
+            b'/' if !in_string && bytes.get(i + 1) == Some(&b'/') => return Some(i),
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+fn limit(value: &str) -> Result<u64, SpecError> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| SpecError::InvalidLimit(value.trim().to_string()))
+}
+
+/// Find all benchmark files under each of `roots`, in a stable order.
+///
+/// A root that names a file is returned as is.
+pub fn discover(roots: &[PathBuf]) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for root in roots {
+        if root.is_dir() {
+            walk(root, &mut files)?;
+        } else {
+            files.push(root.clone());
+        }
+    }
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+pub(crate) fn walk(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            walk(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> Result<BenchmarkSpec, SpecError> {
+        BenchmarkSpec::parse(Path::new("test.rs"), source, DirectoryConfig::default())
+    }
+
+    #[test]
+    fn expect_clause_kinds_messages_and_lines() {
+        assert_eq!(
+            expect_clause("verified").unwrap(),
+            (Expectation::Verified, None, None)
+        );
+        assert_eq!(
+            expect_clause(r#"panic message "capacity \"overflow\"" line 12"#).unwrap(),
+            (
+                Expectation::Panic,
+                Some("capacity \"overflow\"".to_string()),
+                Some(12)
+            )
+        );
+        assert_eq!(
+            expect_clause("overflow line 3").unwrap(),
+            (Expectation::Overflow, None, Some(3))
+        );
+    }
+
+    #[test]
+    fn expect_clause_errors() {
+        assert!(matches!(
+            expect_clause("fine"),
+            Err(SpecError::UnknownExpectation(_))
+        ));
+        assert!(matches!(
+            expect_clause("verified line 3"),
+            Err(SpecError::InvalidExpectation(..))
+        ));
+        assert!(matches!(
+            expect_clause(r#"panic message "unterminated"#),
+            Err(SpecError::InvalidExpectation(..))
+        ));
+        assert!(matches!(
+            expect_clause("panic line twelve"),
+            Err(SpecError::InvalidExpectation(..))
+        ));
+        assert!(matches!(
+            expect_clause("panic because"),
+            Err(SpecError::InvalidExpectation(..))
+        ));
+    }
+
+    #[test]
+    fn quoted_strings_round_trip() {
+        for text in ["", "plain", "say \"hi\"", r"back\slash", "\\\""] {
+            let quoted = quote(text);
+            assert_eq!(
+                quoted_string(&format!("{} rest", quoted)),
+                Some((text.to_string(), " rest"))
+            );
+        }
+        assert_eq!(quoted_string("no quotes"), None);
+        assert_eq!(quoted_string(r#""open \""#), None);
+    }
+
+    #[test]
+    fn fails_here_markers() {
+        let spec = parse("// @expect panic\nfn main() {\n    v[3]; // @fails-here\n}\n").unwrap();
+        assert_eq!(spec.failure_line(), Some(3));
+        let spec = parse("// @expect panic\nv[3]; // @fails-here: index out of bounds\n").unwrap();
+        assert_eq!(spec.fails_here, Some(2));
+        let spec = parse("// @expect panic\nlet s = \"// @fails-here\";\n").unwrap();
+        assert_eq!(spec.fails_here, None);
+    }
+
+    #[test]
+    fn malformed_fails_here_markers() {
+        for source in [
+            "// @expect panic\nv[3]; // @fails-hereafter\n",
+            "// @expect panic\nv[3]; // fails @fails-here\n",
+            "// @expect panic\nv[3]; // @fails-here\nv[4]; // @fails-here\n",
+            "// @expect verified\nv[3]; // @fails-here\n",
+            "// @expect panic line 2\nv[3]; // @fails-here\n",
+        ] {
+            assert!(
+                matches!(parse(source), Err(SpecError::InvalidMarker(_))),
+                "{}",
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn skip_names_backends() {
+        let spec = parse("// @expect verified\n// @skip smack mirai\n").unwrap();
+        assert!(spec.skipped_by("smack"));
+        assert!(!spec.skipped_by("klee"));
+        assert!(matches!(
+            parse("// @expect verified\n// @skip cbmc\n"),
+            Err(SpecError::UnknownBackend(_))
+        ));
+    }
+}
@@ -0,0 +1,470 @@
+//! SV-COMP task definitions for the benchmarks.
+//!
+//! BenchExec and the SV-COMP infrastructure describe each verification
+//! task with a YAML file that names the input, the properties to check
+//! and the expected verdict for each property. This module generates
+//! those files from the `@expect`/`@flag` headers, and reads them back.
+
+use crate::cover;
+use crate::exec::Limits;
+use crate::options::BenchmarkOptions;
+use crate::spec::{BenchmarkSpec, Expectation, SpecError};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// The SV-COMP properties that benchmarks can check.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Property {
+    /// No assertion fails and no panic is reached.
+    UnreachCall,
+    /// No arithmetic operation overflows.
+    NoOverflow,
+    /// Every pointer dereference and free is valid.
+    ValidMemsafety,
+}
+
+impl Property {
+    pub const ALL: [Property; 3] = [
+        Property::UnreachCall,
+        Property::NoOverflow,
+        Property::ValidMemsafety,
+    ];
+
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Property::UnreachCall => "unreach-call.prp",
+            Property::NoOverflow => "no-overflow.prp",
+            Property::ValidMemsafety => "valid-memsafety.prp",
+        }
+    }
+
+    /// The contents of the property file.
+    pub fn definition(self) -> &'static str {
+        match self {
+            Property::UnreachCall => "CHECK( init(main()), LTL(G ! call(reach_error())) )\n",
+            Property::NoOverflow => "CHECK( init(main()), LTL(G ! overflow) )\n",
+            Property::ValidMemsafety => {
+                "CHECK( init(main()), LTL(G valid-free) )\n\
+                 CHECK( init(main()), LTL(G valid-deref) )\n\
+                 CHECK( init(main()), LTL(G valid-memtrack) )\n"
+            }
+        }
+    }
+
+    fn from_file_name(path: &str) -> Option<Property> {
+        let name = Path::new(path).file_name()?.to_str()?;
+        Property::ALL
+            .iter()
+            .copied()
+            .find(|p| p.file_name() == name)
+    }
+}
+
+/// A task definition in SV-COMP format version 2.0.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Task {
+    pub format_version: String,
+    pub input_files: InputFiles,
+    pub properties: Vec<PropertyEntry>,
+    #[serde(default)]
+    pub options: TaskOptions,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum InputFiles {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PropertyEntry {
+    pub property_file: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_verdict: Option<bool>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TaskOptions {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// The benchmark's `@flag` options, which SV-COMP has no place for.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub flags: Vec<String>,
+    /// The benchmark's `@expect` header, if the properties do not
+    /// determine it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expect: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum TaskError {
+    Io(io::Error),
+    Yaml(serde_yaml::Error),
+    Spec(SpecError),
+    /// The task does not have exactly one input file.
+    InputFiles,
+    /// The task checks no property that a benchmark header can express.
+    NoProperty,
+    /// The expectation cannot be written into the input file's header.
+    Header(&'static str),
+}
+
+impl fmt::Display for TaskError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TaskError::Io(e) => write!(f, "{}", e),
+            TaskError::Yaml(e) => write!(f, "{}", e),
+            TaskError::Spec(e) => write!(f, "{}", e),
+            TaskError::InputFiles => write!(f, "expected exactly one input file"),
+            TaskError::NoProperty => write!(f, "no supported property"),
+            TaskError::Header(reason) => write!(f, "cannot rewrite the header: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for TaskError {}
+
+impl From<io::Error> for TaskError {
+    fn from(e: io::Error) -> Self {
+        TaskError::Io(e)
+    }
+}
+
+impl From<SpecError> for TaskError {
+    fn from(e: SpecError) -> Self {
+        TaskError::Spec(e)
+    }
+}
+
+impl From<serde_yaml::Error> for TaskError {
+    fn from(e: serde_yaml::Error) -> Self {
+        TaskError::Yaml(e)
+    }
+}
+
+/// The properties that a benchmark checks, with their expected verdicts.
+///
+/// Every panic, including reaching `verifier::unreachable!`, reports an
+/// error in the same way as a failed assertion, so all failures other
+/// than overflow and memory-safety violations become `unreach-call`
+/// violations. `unknown` benchmarks have no expected verdict.
+pub fn properties(spec: &BenchmarkSpec) -> Vec<(Property, Option<bool>)> {
+    match spec.expect {
+        Expectation::Verified if spec.options.integer_overflow => vec![
+            (Property::UnreachCall, Some(true)),
+            (Property::NoOverflow, Some(true)),
+        ],
+        Expectation::Verified => vec![(Property::UnreachCall, Some(true))],
+        Expectation::Unknown => vec![(Property::UnreachCall, None)],
+        Expectation::Overflow => vec![(Property::NoOverflow, Some(false))],
+        Expectation::MemorySafety => vec![(Property::ValidMemsafety, Some(false))],
+        _ => vec![(Property::UnreachCall, Some(false))],
+    }
+}
+
+// The expectation that a set of property verdicts describes.
+fn implied_expectation(verdicts: impl Iterator<Item = (Property, Option<bool>)>) -> Expectation {
+    let mut expect = Expectation::Verified;
+    for (property, verdict) in verdicts {
+        match verdict {
+            Some(true) => {}
+            Some(false) => {
+                return match property {
+                    Property::UnreachCall => Expectation::Error,
+                    Property::NoOverflow => Expectation::Overflow,
+                    Property::ValidMemsafety => Expectation::MemorySafety,
+                }
+            }
+            None => expect = Expectation::Unknown,
+        }
+    }
+    expect
+}
+
+impl Task {
+    /// The task for `spec`, to be written to `task_path`.
+    pub fn from_spec(spec: &BenchmarkSpec, task_path: &Path, properties_dir: &Path) -> Task {
+        let task_dir = task_path.parent().unwrap_or_else(|| Path::new(""));
+        let input = relative_path(task_dir, &spec.path);
+        let properties = properties(spec);
+        let expect = spec.expect_clause();
+        let implied = implied_expectation(properties.iter().copied()).to_string();
+        Task {
+            format_version: "2.0".to_string(),
+            input_files: InputFiles::One(input.to_string_lossy().into_owned()),
+            properties: properties
+                .into_iter()
+                .map(|(property, verdict)| PropertyEntry {
+                    property_file: relative_path(
+                        task_dir,
+                        &properties_dir.join(property.file_name()),
+                    )
+                    .to_string_lossy()
+                    .into_owned(),
+                    expected_verdict: verdict,
+                })
+                .collect(),
+            options: TaskOptions {
+                language: Some("Rust".to_string()),
+                flags: spec.options.to_flags(),
+                expect: if expect == implied {
+                    None
+                } else {
+                    Some(expect)
+                },
+            },
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Task, TaskError> {
+        Ok(serde_yaml::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn to_yaml(&self) -> Result<String, TaskError> {
+        Ok(serde_yaml::to_string(self)?)
+    }
+
+    /// The benchmark file, relative to the directory holding the task.
+    pub fn input_file(&self) -> Result<&str, TaskError> {
+        match &self.input_files {
+            InputFiles::One(file) => Ok(file),
+            InputFiles::Many(files) if files.len() == 1 => Ok(&files[0]),
+            InputFiles::Many(_) => Err(TaskError::InputFiles),
+        }
+    }
+
+    /// Recover the header of the benchmark that this task describes.
+    ///
+    /// Properties that are not in `Property::ALL` are ignored. Without
+    /// an `expect` option, every `unreach-call` violation comes back as
+    /// `error`.
+    pub fn to_spec(&self, task_path: &Path) -> Result<BenchmarkSpec, TaskError> {
+        let verdicts: Vec<(Property, Option<bool>)> = self
+            .properties
+            .iter()
+            .filter_map(|entry| {
+                Property::from_file_name(&entry.property_file)
+                    .map(|property| (property, entry.expected_verdict))
+            })
+            .collect();
+        if verdicts.is_empty() {
+            return Err(TaskError::NoProperty);
+        }
+        let checks_overflow = verdicts.iter().any(|(p, _)| *p == Property::NoOverflow);
+
+        let mut options = BenchmarkOptions::parse(self.options.flags.iter().map(String::as_str))?;
+        options.integer_overflow |= checks_overflow;
+
+        let task_dir = task_path.parent().unwrap_or_else(|| Path::new(""));
+        let path = task_dir.join(self.input_file()?);
+        // A missing input is reported when the benchmark is built.
+        let cover_points = fs::read_to_string(&path)
+            .map(|source| cover::points(&source))
+            .unwrap_or_default();
+        let mut spec = BenchmarkSpec {
+            path,
+            expect: implied_expectation(verdicts.into_iter()),
+            expect_message: None,
+            expect_line: None,
+            fails_here: None,
+            options,
+            skip: false,
+            skip_backends: Vec::new(),
+            limits: Limits::default(),
+            cover_points,
+        };
+        if let Some(expect) = &self.options.expect {
+            spec.set_expect(expect)?;
+        }
+        Ok(spec)
+    }
+}
+
+/// The path of a benchmark's task definition.
+pub fn task_path(benchmark: &Path) -> PathBuf {
+    benchmark.with_extension("yml")
+}
+
+/// Write a task definition next to each benchmark, and the property
+/// files into `properties_dir`. Returns the task files written.
+pub fn export(specs: &[BenchmarkSpec], properties_dir: &Path) -> Result<Vec<PathBuf>, TaskError> {
+    fs::create_dir_all(properties_dir)?;
+    for property in &Property::ALL {
+        fs::write(
+            properties_dir.join(property.file_name()),
+            property.definition(),
+        )?;
+    }
+
+    let mut written = Vec::new();
+    for spec in specs {
+        let path = task_path(&spec.path);
+        let task = Task::from_spec(spec, &path, properties_dir);
+        fs::write(&path, task.to_yaml()?)?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// Replace the `@expect` and `@flag` header of the benchmark that a
+/// task describes with one derived from the task.
+pub fn import(task_path: &Path) -> Result<PathBuf, TaskError> {
+    let spec = Task::load(task_path)?.to_spec(task_path)?;
+    let source = fs::read_to_string(&spec.path)?;
+
+    let flags = spec.options.to_flags();
+    let mut flag_line = if flags.is_empty() {
+        None
+    } else {
+        Some(format!("// @flag {}", flags.join(" ")))
+    };
+    let expect = spec.expect_clause();
+    if expect.contains(['\n', '\r']) {
+        return Err(TaskError::Header("the message has a line break"));
+    }
+    let mut expect_line = Some(format!("// @expect {}", expect));
+
+    // The new header lines replace the old `@flag` and `@expect` lines
+    // where they are, so that the lines of the code do not move. Old
+    // lines that are left over become blank, and new lines that do not
+    // fit go after them or at the top of the file, which is only allowed
+    // if no line is expected.
+    let mut lines: Vec<String> = source.lines().map(str::to_string).collect();
+    let mut last_slot = None;
+    for (i, line) in lines.iter_mut().enumerate() {
+        let trimmed = line.trim_start();
+        let new = if trimmed.starts_with("// @expect") {
+            expect_line.take()
+        } else if trimmed.starts_with("// @flag") {
+            flag_line.take()
+        } else {
+            continue;
+        };
+        *line = new.unwrap_or_default();
+        last_slot = Some(i);
+    }
+    let extra: Vec<String> = flag_line.into_iter().chain(expect_line).collect();
+    if !extra.is_empty() {
+        if spec.expect_line.is_some() {
+            return Err(TaskError::Header(
+                "more header lines would move the expected line",
+            ));
+        }
+        match last_slot {
+            Some(slot) => {
+                lines.splice(slot + 1..slot + 1, extra);
+            }
+            None => {
+                lines.splice(0..0, extra.into_iter().chain(Some(String::new())));
+            }
+        }
+    }
+
+    let mut text = lines.join("\n");
+    text.push('\n');
+    fs::write(&spec.path, text)?;
+    Ok(spec.path)
+}
+
+// The path of `to` relative to the directory `from`.
+fn relative_path(from: &Path, to: &Path) -> PathBuf {
+    let from = absolute(from);
+    let to = absolute(to);
+    let common = from
+        .components()
+        .zip(to.components())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut path = PathBuf::new();
+    for _ in from.components().skip(common) {
+        path.push("..");
+    }
+    for component in to.components().skip(common) {
+        path.push(component);
+    }
+    path
+}
+
+fn absolute(path: &Path) -> PathBuf {
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().unwrap_or_default().join(path)
+    };
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                result.pop();
+            }
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A fresh directory for one test.
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "bench-runner-svcomp-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn export_then_import_restores_the_header() {
+        let dir = scratch("round-trip");
+        let benchmark = dir.join("boom.rs");
+        let source = "// @flag --unroll=4 --integer-overflow\n\
+                      // @expect panic message \"say \\\"boom\\\"\" line 4\n\
+                      fn main() {\n    panic!(\"say \\\"boom\\\"\");\n}\n";
+        fs::write(&benchmark, source).unwrap();
+        let spec = BenchmarkSpec::load(&benchmark).unwrap();
+        let written = export(&[spec], &dir.join("properties")).unwrap();
+        assert_eq!(written, [task_path(&benchmark)]);
+
+        let edited = source
+            .replace("--unroll=4 --integer-overflow", "--bit-precise")
+            .replace("panic message \"say \\\"boom\\\"\" line 4", "verified");
+        fs::write(&benchmark, edited).unwrap();
+        assert_eq!(import(&written[0]).unwrap(), benchmark);
+        assert_eq!(fs::read_to_string(&benchmark).unwrap(), source);
+        let spec = BenchmarkSpec::load(&benchmark).unwrap();
+        assert_eq!(spec.expect, Expectation::Panic);
+        assert_eq!(spec.expect_message.as_deref(), Some("say \"boom\""));
+        assert_eq!(spec.expect_line, Some(4));
+        assert_eq!(spec.options.loop_bound, Some(4));
+        assert!(spec.options.integer_overflow && !spec.options.bit_precise);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn import_adds_a_missing_header_at_the_top() {
+        let dir = scratch("missing-header");
+        let benchmark = dir.join("fine.rs");
+        fs::write(&benchmark, "// @expect verified\nfn main() {}\n").unwrap();
+        let spec = BenchmarkSpec::load(&benchmark).unwrap();
+        let written = export(&[spec], &dir.join("properties")).unwrap();
+
+        fs::write(&benchmark, "fn main() {}\n").unwrap();
+        import(&written[0]).unwrap();
+        assert_eq!(
+            fs::read_to_string(&benchmark).unwrap(),
+            "// @expect verified\n\nfn main() {}\n"
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
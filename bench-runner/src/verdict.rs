@@ -0,0 +1,199 @@
+use crate::spec::Expectation;
+use std::fmt;
+
+/// What a verifier reported for a benchmark.
+///
+/// The kinds of failure are those of `Expectation`, with `Error`
+/// standing for a failure that the verifier does not explain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Verified,
+    Error,
+    Overflow,
+    Reachable,
+    Assertion,
+    Panic,
+    IndexOutOfBounds,
+    DivisionByZero,
+    BorrowError,
+    UnwrapNone,
+    MemorySafety,
+    Timeout,
+    OutOfMemory,
+    Unknown,
+}
+
+impl Verdict {
+    /// Whether this verdict satisfies what the benchmark declares.
+    ///
+    /// A failure that the verifier does not explain satisfies any kind
+    /// of failure except overflow and reachability, which every backend
+    /// reports distinctly. Any panic satisfies `panic`.
+    pub fn satisfies(self, expect: Expectation) -> bool {
+        match expect {
+            Expectation::Verified => self == Verdict::Verified,
+            Expectation::Error => self.is_failure(),
+            Expectation::Overflow => self == Verdict::Overflow,
+            Expectation::Reachable => self == Verdict::Reachable,
+            Expectation::Panic => self.is_panic() || self == Verdict::Error,
+            Expectation::Unknown => !self.is_definite(),
+            Expectation::Assertion => matches!(self, Verdict::Assertion | Verdict::Error),
+            Expectation::IndexOutOfBounds => {
+                matches!(self, Verdict::IndexOutOfBounds | Verdict::Error)
+            }
+            Expectation::DivisionByZero => matches!(self, Verdict::DivisionByZero | Verdict::Error),
+            Expectation::BorrowError => matches!(self, Verdict::BorrowError | Verdict::Error),
+            Expectation::UnwrapNone => matches!(self, Verdict::UnwrapNone | Verdict::Error),
+            Expectation::MemorySafety => matches!(self, Verdict::MemorySafety | Verdict::Error),
+        }
+    }
+
+    /// Whether the verifier found a failure.
+    pub fn is_failure(self) -> bool {
+        self.is_definite() && self != Verdict::Verified
+    }
+
+    /// Whether the verifier reached a verdict at all.
+    pub fn is_definite(self) -> bool {
+        !matches!(
+            self,
+            Verdict::Timeout | Verdict::OutOfMemory | Verdict::Unknown
+        )
+    }
+
+    fn is_panic(self) -> bool {
+        self.is_failure() && !matches!(self, Verdict::Error | Verdict::MemorySafety)
+    }
+
+    /// Classify a panic message, or the message of a failed check, by
+    /// the wording that rustc, std and the verifiers use.
+    ///
+    /// Returns `None` for messages that do not say what went wrong.
+    pub fn of_message(message: &str) -> Option<Verdict> {
+        const KINDS: &[(&str, Verdict)] = &[
+            ("with overflow", Verdict::Overflow),
+            ("attempt to negate", Verdict::Overflow),
+            ("entered unreachable code", Verdict::Reachable),
+            ("divide by zero", Verdict::DivisionByZero),
+            ("remainder with a divisor of zero", Verdict::DivisionByZero),
+            ("index out of bounds", Verdict::IndexOutOfBounds),
+            ("already borrowed", Verdict::BorrowError),
+            ("already mutably borrowed", Verdict::BorrowError),
+            ("on a `None` value", Verdict::UnwrapNone),
+            ("assertion failed", Verdict::Assertion),
+            ("assertion `left", Verdict::Assertion),
+            // From `#[verifier::ensures]` and `verifier::invariant!`.
+            ("postcondition `", Verdict::Assertion),
+            ("loop invariant `", Verdict::Assertion),
+            ("verification failed", Verdict::Assertion),
+            ("memory error", Verdict::MemorySafety),
+            ("invalid pointer dereference", Verdict::MemorySafety),
+            ("invalid memory deallocation", Verdict::MemorySafety),
+        ];
+        KINDS
+            .iter()
+            .find(|(wording, _)| message.contains(wording))
+            .map(|(_, verdict)| *verdict)
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Verdict::Verified => "verified",
+            Verdict::Error => "error",
+            Verdict::Overflow => "overflow",
+            Verdict::Reachable => "reachable",
+            Verdict::Assertion => "assertion",
+            Verdict::Panic => "panic",
+            Verdict::IndexOutOfBounds => "index-out-of-bounds",
+            Verdict::DivisionByZero => "division-by-zero",
+            Verdict::BorrowError => "borrow-error",
+            Verdict::UnwrapNone => "unwrap-none",
+            Verdict::MemorySafety => "memory-safety",
+            Verdict::Timeout => "timeout",
+            Verdict::OutOfMemory => "out-of-memory",
+            Verdict::Unknown => "unknown",
+        };
+        f.write_str(s)
+    }
+}
+
+/// How a verdict compares with the declared expectation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// The verifier agreed with `@expect`.
+    Pass,
+    /// The verifier gave a definite answer that disagrees with `@expect`.
+    Fail,
+    /// The verifier gave no definite answer (it ran out of time or
+    /// memory, or its output was not recognised).
+    Unexpected,
+}
+
+impl Outcome {
+    pub fn judge(verdict: Verdict, expect: Expectation) -> Outcome {
+        if verdict.satisfies(expect) {
+            Outcome::Pass
+        } else if verdict.is_definite() {
+            Outcome::Fail
+        } else {
+            Outcome::Unexpected
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Outcome::Pass => "PASSED",
+            Outcome::Fail => "FAILED",
+            Outcome::Unexpected => "UNEXPECTED",
+        };
+        f.write_str(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_are_classified() {
+        let cases = [
+            ("attempt to add with overflow", Verdict::Overflow),
+            ("attempt to negate with overflow", Verdict::Overflow),
+            (
+                "internal error: entered unreachable code",
+                Verdict::Reachable,
+            ),
+            ("attempt to divide by zero", Verdict::DivisionByZero),
+            (
+                "index out of bounds: the len is 3 but the index is 3",
+                Verdict::IndexOutOfBounds,
+            ),
+            (
+                "already mutably borrowed: BorrowError",
+                Verdict::BorrowError,
+            ),
+            (
+                "called `Option::unwrap()` on a `None` value",
+                Verdict::UnwrapNone,
+            ),
+            ("assertion failed: x > 0", Verdict::Assertion),
+            ("assertion `left == right` failed", Verdict::Assertion),
+            ("postcondition `ret > 0` does not hold", Verdict::Assertion),
+            ("memory error: out of bound pointer", Verdict::MemorySafety),
+        ];
+        for (message, verdict) in cases.iter() {
+            assert_eq!(Verdict::of_message(message), Some(*verdict), "{}", message);
+        }
+    }
+
+    #[test]
+    fn unexplained_messages_are_not_classified() {
+        assert_eq!(Verdict::of_message("explicit panic"), None);
+        assert_eq!(Verdict::of_message("warning: unreachable pattern"), None);
+        assert_eq!(Verdict::of_message(""), None);
+    }
+}
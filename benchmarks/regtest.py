@@ -88,9 +88,15 @@ def metadata(file):
       if match:
         m['flags'] += shlex.split(match.group(1).strip())
 
-      match = re.search(r'@expect (.*)',line)
+      match = re.search(r'@expect (\S+)',line)
       if match:
-        m['expect'] = match.group(1).strip()
+        # SMACK only distinguishes overflow and reachability from
+        # other failures, so the finer kinds all expect an error
+        m['expect'] = match.group(1)
+        if m['expect'] in ['assertion', 'panic', 'index-out-of-bounds',
+                           'division-by-zero', 'borrow-error', 'unwrap-none',
+                           'memory-safety']:
+          m['expect'] = 'error'
 
   if not m['skip']:
     if not 'expect' in m:
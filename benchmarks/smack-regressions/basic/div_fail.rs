@@ -1,4 +1,4 @@
-// @expect error
+// @expect assertion
 
 pub fn main() {
     let a = 2;
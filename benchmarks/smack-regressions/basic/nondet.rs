@@ -0,0 +1,17 @@
+// @expect verified
+
+fn two() -> u8 {
+    2
+}
+
+pub fn main() {
+    // Placeholders that start with a path are values, not types.
+    let a = verifier::nondet!(two());
+    let b = verifier::nondet!(Some(3u8)).unwrap_or(0);
+    let c = 4u8;
+    let c = verifier::nondet!(c);
+    let d = verifier::nondet!(type u8);
+    let e: u8 = verifier::nondet!();
+    let sum: u32 = [a, b, c, d, e].iter().map(|&x| u32::from(x)).sum();
+    verifier::assert!(sum <= 5 * 255);
+}
@@ -0,0 +1,12 @@
+// @expect verified
+
+#[verifier::requires(a <= std::u32::MAX / 2)] // avoid overflow
+#[verifier::ensures(|ret| *ret == 2 * a)]
+fn double(a: u32) -> u32 {
+    a * 2
+}
+
+pub fn main() {
+    let a = verifier::nondet!(2u32);
+    double(a);
+}
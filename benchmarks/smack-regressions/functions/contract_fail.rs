@@ -0,0 +1,12 @@
+// @expect assertion
+
+#[verifier::requires(a <= std::u32::MAX / 2)] // avoid overflow
+#[verifier::ensures(|ret| *ret == 2 * a)] // @fails-here
+fn double(a: u32) -> u32 {
+    a + 2
+}
+
+pub fn main() {
+    let a = verifier::nondet!(3u32);
+    double(a);
+}
@@ -55,7 +55,7 @@ pub fn main() {
     let q3 = swapem(p3);
     verifier::assert_eq!(q2.x, y2);
     verifier::assert_eq!(q2.y, x2);
-    verifier::assert_ne!(q3.x, y3);
+    verifier::assert_ne!(q3.x, y3); // @fails-here
     verifier::assert_eq!(q3.y, z3);
     verifier::assert_eq!(q3.z, x3);
 }
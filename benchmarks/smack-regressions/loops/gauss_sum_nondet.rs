@@ -6,6 +6,7 @@ pub fn main() {
     let b = verifier::nondet!(7u64);
     verifier::assume!(b < 5 && b > 1);
     for i in 0..b as u64 {
+        verifier::invariant!(2 * sum + i == i * i);
         sum += i;
     }
     verifier::assert_eq!(2 * sum, b * (b - 1));
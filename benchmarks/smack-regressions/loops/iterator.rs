@@ -14,6 +14,7 @@ pub fn main() {
     let n = verifier::nondet!(6u64);
     verifier::assume!(n < 5);
     for i in 1..n + 1 as u64 {
+        verifier::invariant!(a == fac(i - 1));
         a *= i;
     }
     verifier::assert_eq!(a, fac(n)); // a == 6!
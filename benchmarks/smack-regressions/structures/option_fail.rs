@@ -21,6 +21,6 @@ pub fn main() {
     let b = safe_div(x, y);
     match b {
         Some(c) => verifier::assert_eq!(c, x / y),
-        None => verifier::unreachable!(), // Division by zero should return None
+        None => verifier::unreachable!(), // @fails-here: division by zero should return None
     };
 }
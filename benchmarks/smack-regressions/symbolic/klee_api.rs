@@ -0,0 +1,23 @@
+// @expect verified
+// @skip smack mirai
+
+use klee_annotations::*;
+
+pub fn main() {
+    let x = klee_range(0, 10, "x");
+    let y = klee_int("y");
+    klee_prefer_cex(&y, y > 0);
+    klee_warning_once("x and y are set");
+
+    klee_open_merge();
+    let z = if y < 0 { x } else { 9 - x };
+    klee_close_merge();
+    verifier::assert!(0 <= z && z < 10);
+
+    let v = klee_get_value_i32(x);
+    verifier::assert!(0 <= v && v < 10);
+    verifier::assert!(!klee_is_symbolic(7));
+
+    let bytes = [0u8; 4];
+    klee_check_memory_access(bytes.as_ptr(), bytes.len());
+}
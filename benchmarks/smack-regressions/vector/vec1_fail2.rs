@@ -11,6 +11,6 @@ pub fn main() {
     verifier::assert_eq!(v[2], 3);
     v[2] = v[0] + v[1];
     verifier::assert_eq!(v[0], 0);
-    verifier::assert_ne!(v[1], 1);
+    verifier::assert_ne!(v[1], 1); // @fails-here
     verifier::assert_eq!(v[2], 1);
 }
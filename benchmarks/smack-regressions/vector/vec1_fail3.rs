@@ -12,5 +12,5 @@ pub fn main() {
     v[2] = v[0] + v[1];
     verifier::assert_eq!(v[0], 0);
     verifier::assert_eq!(v[1], 1);
-    verifier::assert_ne!(v[2], 1);
+    verifier::assert_ne!(v[2], 1); // @fails-here
 }
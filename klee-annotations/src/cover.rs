@@ -0,0 +1,33 @@
+// Counting cover points natively.
+//
+// Each label counts the runs of the process in which its condition held
+// at the point. The first time that happens, the label is printed as
+// `verifier: covered LABEL`, which is also how KLEE reports it, so that
+// tools can tell which points were covered from the output alone.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+static COUNTS: Mutex<Option<HashMap<String, u64>>> = Mutex::new(None);
+
+// Count a cover point whose condition held.
+pub fn verifier_count_cover(label: &str) {
+    let mut counts = COUNTS.lock().unwrap_or_else(|e| e.into_inner());
+    let count = counts
+        .get_or_insert_with(HashMap::new)
+        .entry(label.to_string())
+        .or_insert(0);
+    if *count == 0 {
+        eprintln!("verifier: covered {}", label);
+    }
+    *count += 1;
+}
+
+// The number of times that the cover point `label` has been covered.
+pub fn verifier_cover_count(label: &str) -> u64 {
+    let counts = COUNTS.lock().unwrap_or_else(|e| e.into_inner());
+    counts
+        .as_ref()
+        .and_then(|counts| counts.get(label).copied())
+        .unwrap_or(0)
+}
@@ -0,0 +1,49 @@
+// Running verification harnesses natively.
+//
+// A run whose assumptions do not hold is rejected: inside a harness it
+// unwinds with a Rejected payload, which the harness catches so that
+// the run passes vacuously, and elsewhere the program exits with
+// VERIFIER_REJECTED_EXIT_CODE. Either way it is distinct from a panic.
+
+use std::any::Any;
+use std::cell::Cell;
+use std::panic;
+use std::process;
+
+// The exit code of a program whose run was rejected outside a harness.
+pub const VERIFIER_REJECTED_EXIT_CODE: i32 = 86;
+
+thread_local! {
+    static IN_HARNESS: Cell<bool> = const { Cell::new(false) };
+}
+
+// The payload that rejected runs unwind with.
+pub struct Rejected;
+
+// Reject the current run natively.
+pub fn verifier_reject_run() -> ! {
+    if IN_HARNESS.with(|in_harness| in_harness.get()) {
+        // Unlike panic!, resume_unwind does not call the panic hook.
+        panic::resume_unwind(Box::new(Rejected))
+    }
+    eprintln!("verifier: run rejected by an assumption");
+    process::exit(VERIFIER_REJECTED_EXIT_CODE)
+}
+
+// Whether a run unwound because it was rejected.
+pub fn verifier_is_rejection(payload: &(dyn Any + Send)) -> bool {
+    payload.is::<Rejected>()
+}
+
+// Run `harness` once, returning false if the run was rejected. Any
+// other panic unwinds as usual.
+pub fn verifier_run_harness(harness: fn()) -> bool {
+    let outer = IN_HARNESS.with(|in_harness| in_harness.replace(true));
+    let result = panic::catch_unwind(harness);
+    IN_HARNESS.with(|in_harness| in_harness.set(outer));
+    match result {
+        Ok(()) => true,
+        Err(payload) if verifier_is_rejection(&*payload) => false,
+        Err(payload) => panic::resume_unwind(payload),
+    }
+}
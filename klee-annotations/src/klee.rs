@@ -0,0 +1,235 @@
+use std::os::raw;
+use std::ffi::CString;
+
+pub fn verifier_assume(cond: bool) {
+    extern "C" { fn klee_assume(cond: usize); }
+    unsafe { klee_assume(if cond {1} else {0}) }
+}
+
+pub fn verifier_abstract_value<T: crate::VerifierValue>(_t: T) -> T {
+    extern "C" { fn klee_make_symbolic(data: *mut raw::c_void, length: usize, name: *const raw::c_char); }
+
+    let mut r = T::default();
+    unsafe {
+        let data   = std::mem::transmute(&mut r);
+        let length = std::mem::size_of::<T>();
+        let null = 0 as *const i8;
+        klee_make_symbolic(data, length, null)
+    }
+    return r;
+}
+
+// Make a value symbolic. KLEE can only make a whole memory object
+// symbolic, so the value is replaced by a new symbolic one, which also
+// works for fields of structs and elements of arrays.
+pub fn verifier_make_symbolic<T: crate::VerifierValue>(value: &mut T) {
+    *value = verifier_abstract_value(*value)
+}
+
+// A value from start (inclusive) to end (exclusive), like klee_range
+// but for any integer type. An empty range rejects the path.
+pub fn verifier_range<T: crate::VerifierInteger>(start: T, end: T) -> T {
+    if end.to_i128() - start.to_i128() == 1 {
+        return start;
+    }
+    let r = verifier_abstract_value(start);
+    verifier_assume(start <= r && r < end);
+    r
+}
+
+// Whether no byte of the value is symbolic. Only the value itself is
+// checked, not memory that it points to.
+pub fn verifier_is_concrete<T: ?Sized>(value: &T) -> bool {
+    let data = value as *const T as *const u8;
+    let length = std::mem::size_of_val(value);
+    (0..length).all(|i| !klee_is_symbolic(unsafe { *data.add(i) } as usize))
+}
+
+// Mark a point that should be reachable with `condition` true. KLEE
+// prints `verifier: covered LABEL` as a warning, once, if it can be.
+pub fn verifier_cover(condition: bool, label: &str) {
+    if condition {
+        klee_warning_once(&format!("verifier: covered {}", label))
+    }
+}
+
+// Reject the current execution with a verification failure.
+//
+// In almost all circumstances, verifier_report_error should
+// be used instead because it generates an error message.
+pub fn verifier_abort() -> ! {
+    extern "C" { fn klee_abort() -> !; }
+
+    unsafe { klee_abort() }
+}
+
+// Reject the current execution path with a verification success.
+// This is equivalent to verifier_assume(false)
+// and the opposite of verifier_report_error.
+//
+// Typical usage is in generating symbolic values when the value
+// does not meet some criteria.
+pub fn verifier_reject() -> ! {
+    extern "C" { fn klee_silent_exit(_ignored: u32) -> !; }
+    unsafe { klee_silent_exit(0) }
+}
+
+// Reject the current execution with a verification failure
+// and an error message.
+pub fn verifier_report_error(message: &str) -> ! {
+    extern "C" {
+        fn klee_report_error(file: *const raw::c_char, line: usize, message: *const raw::c_char, suffix: *const raw::c_char) -> !;
+    }
+
+    let null = 0 as *const i8;
+    let file = null; // ignored by KLEE
+    let line = 0;    // ignored by KLEE
+    let suffix = ""; // ignored by KLEE
+
+    let message = CString::new(message).unwrap();
+    let suffix  = CString::new(suffix).unwrap();
+    unsafe {
+        klee_report_error(file, line, message.as_ptr(), suffix.as_ptr())
+    }
+}
+
+// Like verifier_report_error, for an error at a place in the source.
+//
+// KLEE reports the location of its call to klee_report_error, which is
+// in this file, so the location is also put at the start of the message.
+pub fn verifier_report_error_at(file: &str, line: u32, column: u32, message: &str) -> ! {
+    extern "C" {
+        fn klee_report_error(file: *const raw::c_char, line: usize, message: *const raw::c_char, suffix: *const raw::c_char) -> !;
+    }
+
+    let message = format!("{}:{}:{}: {}", file, line, column, message);
+    let message = CString::new(message).unwrap();
+    let file    = CString::new(file).unwrap();
+    let suffix  = CString::new("").unwrap();
+    unsafe {
+        klee_report_error(file.as_ptr(), line as usize, message.as_ptr(), suffix.as_ptr())
+    }
+}
+
+// Safe wrappers for the rest of the KLEE API, with the names and
+// meaning of the functions in klee.h. Names of symbolic objects are
+// only used by KLEE in its test cases.
+
+// A symbolic value from start (inclusive) to end (exclusive).
+pub fn klee_range(start: i32, end: i32, name: &str) -> i32 {
+    extern "C" { fn klee_range(start: i32, end: i32, name: *const raw::c_char) -> i32; }
+
+    let name = CString::new(name).unwrap();
+    unsafe { klee_range(start, end, name.as_ptr()) }
+}
+
+// A symbolic 32-bit integer.
+pub fn klee_int(name: &str) -> i32 {
+    extern "C" { fn klee_int(name: *const raw::c_char) -> i32; }
+
+    let name = CString::new(name).unwrap();
+    unsafe { klee_int(name.as_ptr()) }
+}
+
+// Whether a value is symbolic on the current path.
+pub fn klee_is_symbolic(n: usize) -> bool {
+    extern "C" { fn klee_is_symbolic(n: usize) -> u32; }
+
+    unsafe { klee_is_symbolic(n) != 0 }
+}
+
+// A concrete value that a symbolic value can have on the current path.
+// The path is not constrained to that value.
+pub fn klee_get_value_i32(value: i32) -> i32 {
+    extern "C" { fn klee_get_value_i32(value: i32) -> i32; }
+
+    unsafe { klee_get_value_i32(value) }
+}
+
+pub fn klee_get_value_i64(value: i64) -> i64 {
+    extern "C" { fn klee_get_value_i64(value: i64) -> i64; }
+
+    unsafe { klee_get_value_i64(value) }
+}
+
+pub fn klee_get_value_f32(value: f32) -> f32 {
+    extern "C" { fn klee_get_valuef(value: f32) -> f32; }
+
+    unsafe { klee_get_valuef(value) }
+}
+
+pub fn klee_get_value_f64(value: f64) -> f64 {
+    extern "C" { fn klee_get_valued(value: f64) -> f64; }
+
+    unsafe { klee_get_valued(value) }
+}
+
+// Ask KLEE to prefer test cases in which `condition` holds, where
+// `object` is one of the symbolic objects.
+pub fn klee_prefer_cex<T: ?Sized>(object: &T, condition: bool) {
+    extern "C" { fn klee_prefer_cex(object: *const raw::c_void, condition: usize); }
+
+    unsafe { klee_prefer_cex(object as *const T as *const raw::c_void, condition as usize) }
+}
+
+// Print the symbolic expression of a value, widened to 64 bits.
+pub fn klee_print_expr<T: crate::VerifierInteger>(message: &str, value: T) {
+    extern "C" { fn klee_print_expr(message: *const raw::c_char, ...); }
+
+    let message = CString::new(message).unwrap();
+    unsafe { klee_print_expr(message.as_ptr(), value.to_i128() as i64) }
+}
+
+pub fn klee_warning(message: &str) {
+    extern "C" { fn klee_warning(message: *const raw::c_char); }
+
+    let message = CString::new(message).unwrap();
+    unsafe { klee_warning(message.as_ptr()) }
+}
+
+// Like klee_warning, but each message is only printed the first time.
+pub fn klee_warning_once(message: &str) {
+    extern "C" { fn klee_warning_once(message: *const raw::c_char); }
+
+    let message = CString::new(message).unwrap();
+    unsafe { klee_warning_once(message.as_ptr()) }
+}
+
+// Whether KLEE forks on symbolic branches. Without forking, it follows
+// one feasible side of each branch.
+pub fn klee_set_forking(enable: bool) {
+    extern "C" { fn klee_set_forking(enable: u32); }
+
+    unsafe { klee_set_forking(enable as u32) }
+}
+
+// The paths that fork between klee_open_merge and klee_close_merge are
+// merged again at klee_close_merge. KLEE must be run with
+// --use-merge for this to have an effect.
+pub fn klee_open_merge() {
+    extern "C" { fn klee_open_merge(); }
+
+    unsafe { klee_open_merge() }
+}
+
+pub fn klee_close_merge() {
+    extern "C" { fn klee_close_merge(); }
+
+    unsafe { klee_close_merge() }
+}
+
+// Tell KLEE that `length` bytes at a fixed address, such as a memory
+// mapped device, are memory that the program may access.
+pub fn klee_define_fixed_object(address: usize, length: usize) {
+    extern "C" { fn klee_define_fixed_object(address: *mut raw::c_void, length: usize); }
+
+    unsafe { klee_define_fixed_object(address as *mut raw::c_void, length) }
+}
+
+// Report an error if `length` bytes at `address` are not all inside one
+// object that the program may access.
+pub fn klee_check_memory_access(address: *const u8, length: usize) {
+    extern "C" { fn klee_check_memory_access(address: *const raw::c_void, length: usize); }
+
+    unsafe { klee_check_memory_access(address as *const raw::c_void, length) }
+}
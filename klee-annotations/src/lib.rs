@@ -1,70 +1,72 @@
 #![cfg_attr(feature = "verifier-panic-handler", feature(panic_info_message))]
 
-use std::os::raw;
-use std::ffi::CString;
-use std::default::Default;
+// The KLEE functions below are only bound when building for KLEE.
+// Otherwise they have native implementations, which use pseudo-random
+// values when the verifier-random feature is enabled.
+#[cfg(feature = "verifier-klee")]
+mod klee;
+#[cfg(feature = "verifier-klee")]
+pub use klee::*;
 
-pub fn verifier_assume(cond: bool) {
-    extern "C" { fn klee_assume(cond: usize); }
-    unsafe { klee_assume(if cond {1} else {0}) }
-}
+#[cfg(not(feature = "verifier-klee"))]
+mod native;
+#[cfg(not(feature = "verifier-klee"))]
+pub use native::*;
 
-pub fn verifier_verify(cond: bool) {
-    if !cond {
-        verifier_report_error("verification failed")
-    }
-}
+#[cfg(all(feature = "verifier-random", not(feature = "verifier-klee")))]
+mod random;
+#[cfg(all(feature = "verifier-random", not(feature = "verifier-klee")))]
+pub use random::*;
 
-pub fn verifier_abstract_value<T: Default>(_t: T) -> T {
-    extern "C" { fn klee_make_symbolic(data: *mut raw::c_void, length: usize, name: *const raw::c_char); }
+mod harness;
+pub use harness::*;
 
-    let mut r = T::default();
-    unsafe {
-        let data   = std::mem::transmute(&mut r);
-        let length = std::mem::size_of::<T>();
-        let null = 0 as *const i8;
-        klee_make_symbolic(data, length, null)
-    }
-    return r;
+mod cover;
+pub use cover::*;
+
+mod sealed {
+    pub trait Sealed {}
 }
 
-// Reject the current execution with a verification failure.
-//
-// In almost all circumstances, verifier_report_error should
-// be used instead because it generates an error message.
-pub fn verifier_abort() -> ! {
-    extern "C" { fn klee_abort() -> !; }
+// The types for which any bit pattern is a valid value: the integer and
+// floating point types. Only these can be given arbitrary bytes by
+// verifier_abstract_value and verifier_make_symbolic, so the trait is
+// sealed.
+pub trait VerifierValue: Copy + Default + sealed::Sealed {}
 
-    unsafe { klee_abort() }
+macro_rules! impl_verifier_value {
+    ( $( $ty:ty )* ) => {
+        $(
+            impl sealed::Sealed for $ty {}
+            impl VerifierValue for $ty {}
+        )*
+    }
 }
 
-// Reject the current execution path with a verification success.
-// This is equivalent to verifier_assume(false)
-// and the opposite of verifier_report_error.
-//
-// Typical usage is in generating symbolic values when the value
-// does not meet some criteria.
-pub fn verifier_reject() -> ! {
-    extern "C" { fn klee_silent_exit(_ignored: u32) -> !; }
-    unsafe { klee_silent_exit(0) }
+impl_verifier_value! { u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize f32 f64 }
+
+// The integer types that verifier_range can produce.
+pub trait VerifierInteger: VerifierValue + PartialOrd {
+    fn to_i128(self) -> i128;
+    fn from_i128(value: i128) -> Self;
 }
 
-// Reject the current execution with a verification failure
-// and an error message.
-pub fn verifier_report_error(message: &str) -> ! {
-    extern "C" {
-        fn klee_report_error(file: *const raw::c_char, line: usize, message: *const raw::c_char, suffix: *const raw::c_char) -> !;
+macro_rules! impl_verifier_integer {
+    ( $( $ty:ty )* ) => {
+        $(
+            impl VerifierInteger for $ty {
+                fn to_i128(self) -> i128 { self as i128 }
+                fn from_i128(value: i128) -> Self { value as $ty }
+            }
+        )*
     }
+}
 
-    let null = 0 as *const i8;
-    let file = null; // ignored by KLEE
-    let line = 0;    // ignored by KLEE
-    let suffix = ""; // ignored by KLEE
+impl_verifier_integer! { u8 u16 u32 u64 usize i8 i16 i32 i64 isize }
 
-    let message = CString::new(message).unwrap();
-    let suffix  = CString::new(suffix).unwrap();
-    unsafe {
-        klee_report_error(file, line, message.as_ptr(), suffix.as_ptr())
+pub fn verifier_verify(cond: bool) {
+    if !cond {
+        verifier_report_error("verification failed")
     }
 }
 
@@ -81,7 +83,10 @@ fn verifier_panic_hook(info: &PanicInfo) {
         None => message.write_str("panic"),
         Some(m) => message.write_fmt(*m)
     }.unwrap();
-    verifier_report_error(&message)
+    match info.location() {
+        None => verifier_report_error(&message),
+        Some(l) => verifier_report_error_at(l.file(), l.line(), l.column(), &message)
+    }
 }
 
 // Calling this before starting verification ensures that
@@ -0,0 +1,127 @@
+// Native implementations of the verifier primitives, used by every
+// build that is not for KLEE.
+//
+// Assumptions that do not hold reject the run and errors panic. Values
+// come from the random module with verifier-random and are otherwise
+// the placeholders that the caller passes, as with verifier::nondet!.
+
+use crate::cover::verifier_count_cover;
+use crate::harness::verifier_reject_run;
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+#[cfg(feature = "verifier-random")]
+use crate::random::{verifier_abstract_value, verifier_range};
+
+pub fn verifier_assume(cond: bool) {
+    if !cond {
+        verifier_reject()
+    }
+}
+
+#[cfg(not(feature = "verifier-random"))]
+pub fn verifier_abstract_value<T: crate::VerifierValue>(t: T) -> T {
+    t
+}
+
+#[cfg(not(feature = "verifier-random"))]
+pub fn verifier_make_symbolic<T: crate::VerifierValue>(_value: &mut T) {}
+
+// The start of the range, which must not be empty.
+#[cfg(not(feature = "verifier-random"))]
+pub fn verifier_range<T: crate::VerifierInteger>(start: T, end: T) -> T {
+    if start >= end {
+        verifier_reject()
+    }
+    start
+}
+
+pub fn verifier_cover(condition: bool, label: &str) {
+    if condition {
+        verifier_count_cover(label)
+    }
+}
+
+// Native values are never symbolic.
+pub fn verifier_is_concrete<T: ?Sized>(_value: &T) -> bool {
+    true
+}
+
+pub fn verifier_abort() -> ! {
+    panic!("verifier_abort")
+}
+
+pub fn verifier_reject() -> ! {
+    verifier_reject_run()
+}
+
+pub fn verifier_report_error(message: &str) -> ! {
+    panic!("{}", message)
+}
+
+pub fn verifier_report_error_at(file: &str, line: u32, column: u32, message: &str) -> ! {
+    panic!("{}:{}:{}: {}", file, line, column, message)
+}
+
+// The rest of the KLEE API. Values are never symbolic, so most of these
+// do nothing or return their argument.
+
+pub fn klee_range(start: i32, end: i32, _name: &str) -> i32 {
+    verifier_range(start, end)
+}
+
+pub fn klee_int(_name: &str) -> i32 {
+    verifier_abstract_value(0)
+}
+
+pub fn klee_is_symbolic(_n: usize) -> bool {
+    false
+}
+
+pub fn klee_get_value_i32(value: i32) -> i32 {
+    value
+}
+
+pub fn klee_get_value_i64(value: i64) -> i64 {
+    value
+}
+
+pub fn klee_get_value_f32(value: f32) -> f32 {
+    value
+}
+
+pub fn klee_get_value_f64(value: f64) -> f64 {
+    value
+}
+
+pub fn klee_prefer_cex<T: ?Sized>(_object: &T, _condition: bool) {}
+
+pub fn klee_print_expr<T: crate::VerifierInteger>(message: &str, value: T) {
+    eprintln!("{}: {}", message, value.to_i128())
+}
+
+pub fn klee_warning(message: &str) {
+    eprintln!("warning: {}", message)
+}
+
+// The messages that klee_warning_once has printed.
+static WARNED: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+pub fn klee_warning_once(message: &str) {
+    let mut warned = WARNED.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if warned.get_or_insert_with(HashSet::new).insert(message.to_string()) {
+        klee_warning(message)
+    }
+}
+
+pub fn klee_set_forking(_enable: bool) {}
+
+pub fn klee_open_merge() {}
+
+pub fn klee_close_merge() {}
+
+// Native programs can only access memory that the platform maps, so
+// there is nothing to define or check.
+pub fn klee_define_fixed_object(_address: usize, _length: usize) {}
+
+pub fn klee_check_memory_access(_address: *const u8, _length: usize) {}
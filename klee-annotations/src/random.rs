@@ -0,0 +1,142 @@
+// Native implementations of the verifier primitives that draw
+// pseudo-random values instead of symbolic ones.
+//
+// Runs that are rejected by verifier_assume or verifier_reject are
+// discarded by verifier_run, so a harness that runs many times becomes
+// a cheap property test.
+
+use crate::harness::{verifier_run_harness, VERIFIER_REJECTED_EXIT_CODE};
+use crate::native::verifier_reject;
+use std::cell::Cell;
+use std::env;
+use std::panic;
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// The number of runs that verifier_run makes unless
+// VERIFIER_ITERATIONS says otherwise.
+const DEFAULT_ITERATIONS: u64 = 1000;
+
+thread_local! {
+    static STATE: Cell<u64> = const { Cell::new(0) };
+}
+
+// Set the seed of the generator used by the current thread.
+pub fn verifier_set_seed(seed: u64) {
+    STATE.with(|state| state.set(seed))
+}
+
+// The next value of a SplitMix64 generator.
+fn next_u64() -> u64 {
+    STATE.with(|state| {
+        let next = state.get().wrapping_add(0x9e37_79b9_7f4a_7c15);
+        state.set(next);
+        let mut z = next;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    })
+}
+
+// A pseudo-random bit pattern of `length` bytes.
+//
+// Uniformly random integers almost never satisfy assumptions like
+// `a < 10`, so half of the values are small numbers and a quarter are
+// the extreme values of the integer types of that size.
+fn random_bytes(length: usize) -> Vec<u8> {
+    let mut bytes: Vec<u8> = (0..length).map(|_| next_u64() as u8).collect();
+    match next_u64() % 4 {
+        // A number from -16 to 16.
+        0 | 1 => {
+            let small = (next_u64() % 33) as i8 - 16;
+            let extension = if small < 0 { 0xff } else { 0 };
+            for byte in bytes.iter_mut() {
+                *byte = extension;
+            }
+            if let Some(low) = bytes.first_mut() {
+                *low = small as u8;
+            }
+        }
+        // 0, -1, or the smallest or largest signed value.
+        2 => {
+            let extreme = next_u64() % 4;
+            for byte in bytes.iter_mut() {
+                *byte = if extreme % 2 == 0 { 0 } else { 0xff };
+            }
+            if let Some(high) = bytes.last_mut() {
+                if extreme >= 2 {
+                    *high ^= 0x80;
+                }
+            }
+        }
+        _ => {}
+    }
+    // The bytes were built with the least significant first.
+    if cfg!(target_endian = "big") {
+        bytes.reverse();
+    }
+    bytes
+}
+
+// Like KLEE, this gives every byte of the value an arbitrary value,
+// which VerifierValue restricts to types for which any bit pattern is
+// valid.
+pub fn verifier_abstract_value<T: crate::VerifierValue>(_t: T) -> T {
+    let mut r = T::default();
+    let length = std::mem::size_of::<T>();
+    let bytes = random_bytes(length);
+    unsafe {
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), &mut r as *mut T as *mut u8, length);
+    }
+    r
+}
+
+pub fn verifier_make_symbolic<T: crate::VerifierValue>(value: &mut T) {
+    *value = verifier_abstract_value(*value)
+}
+
+// A value from start (inclusive) to end (exclusive), chosen uniformly.
+pub fn verifier_range<T: crate::VerifierInteger>(start: T, end: T) -> T {
+    if start >= end {
+        verifier_reject()
+    }
+    let width = end.to_i128().wrapping_sub(start.to_i128()) as u128;
+    let offset = (next_u64() as u128 % width) as i128;
+    T::from_i128(start.to_i128() + offset)
+}
+
+// Run `harness` many times with different seeds, discarding rejected
+// runs. A failing run is reported with the seed that reproduces it
+// and then unwinds as usual. If every run is rejected, nothing was
+// checked, so the program exits with VERIFIER_REJECTED_EXIT_CODE.
+//
+// The first seed and the number of runs are taken from the
+// VERIFIER_SEED and VERIFIER_ITERATIONS environment variables.
+pub fn verifier_run(harness: fn()) {
+    let number = |name: &str| env::var(name).ok().and_then(|value| value.parse::<u64>().ok());
+    let first = number("VERIFIER_SEED").unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_nanos() as u64)
+            .unwrap_or(0)
+    });
+    let iterations = number("VERIFIER_ITERATIONS").unwrap_or(DEFAULT_ITERATIONS);
+
+    let mut rejected = 0;
+    for i in 0..iterations {
+        let seed = first.wrapping_add(i);
+        verifier_set_seed(seed);
+        match panic::catch_unwind(|| verifier_run_harness(harness)) {
+            Ok(true) => {}
+            Ok(false) => rejected += 1,
+            Err(payload) => {
+                eprintln!("verifier: run {} of {} failed with VERIFIER_SEED={}", i + 1, iterations, seed);
+                panic::resume_unwind(payload)
+            }
+        }
+    }
+    if iterations > 0 && rejected == iterations {
+        eprintln!("verifier: every one of {} runs was rejected by an assumption", iterations);
+        process::exit(VERIFIER_REJECTED_EXIT_CODE)
+    }
+}
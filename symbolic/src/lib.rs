@@ -17,6 +17,14 @@ use std::marker::PhantomData;
 pub trait Symbolic: 'static {
     /// Generate a symbolic value of `Self`.
     fn symbolic() -> Self;
+
+    /// Replace the value with a symbolic one.
+    fn havoc(&mut self)
+    where
+        Self: Sized,
+    {
+        *self = Self::symbolic()
+    }
 }
 
 impl Symbolic for () {
@@ -35,6 +43,10 @@ macro_rules! impl_symbolic_for_scalars {
                 fn symbolic() -> Self {
                     verifier_abstract_value(<$ty>::default())
                 }
+
+                fn havoc(&mut self) {
+                    verifier_make_symbolic(self)
+                }
             }
         )*
     }
@@ -57,21 +69,20 @@ impl_symbolic_for_scalars! {
     f64;
 }
 
+// bool and char have invalid bit patterns, so they are made from
+// integers that map onto their values.
 impl Symbolic for bool {
     fn symbolic() -> Self {
-        let c = verifier_abstract_value(0u8);
-        verifier_assume(c == 0 || c == 1);
-        c == 1
+        verifier_abstract_value(0u8) & 1 == 1
     }
 }
 
 impl Symbolic for char {
     fn symbolic() -> Self {
-        let c = verifier_abstract_value(0u32);
-        match std::char::from_u32(c) {
-            Some(r) => r,
-            None => verifier_reject()
-        }
+        // Skip the surrogates, U+D800 to U+DFFF.
+        let c = verifier_range(0u32, 0x110000 - 0x800);
+        let c = if c < 0xd800 { c } else { c + 0x800 };
+        std::char::from_u32(c).unwrap()
     }
 }
 
@@ -138,6 +149,12 @@ macro_rules! symbolic_array {
                     $(<$ts as Symbolic>::symbolic()),*
                 ]
             }
+
+            fn havoc(&mut self) {
+                for element in self.iter_mut() {
+                    element.havoc()
+                }
+            }
         }
     };
     ($n: expr,) => {};
@@ -0,0 +1,291 @@
+//! Function contracts, `#[verifier::requires]` and `#[verifier::ensures]`.
+//!
+//! By default a precondition is assumed when the function is entered and
+//! a postcondition is asserted when it returns, using the `verifier`
+//! macros, so that KLEE and native runs check the function's behaviour
+//! wherever it is called. With the `mirai` feature the contracts become
+//! MIRAI's `precondition!` and `postcondition!`, and with the `prusti`
+//! feature they become Prusti specifications, which those tools check
+//! modularly.
+
+use proc_macro::{Delimiter, Group, Spacing, TokenStream, TokenTree};
+
+/// The parts of a function item that contracts need.
+pub struct Function {
+    /// Everything before the body.
+    pub signature: Vec<TokenTree>,
+    /// The return type, or `None` for `()`.
+    pub output: Option<TokenStream>,
+    pub body: Group,
+}
+
+impl Function {
+    pub fn parse(item: TokenStream) -> Option<Function> {
+        let mut signature: Vec<TokenTree> = item.into_iter().collect();
+        let body = match signature.pop() {
+            Some(TokenTree::Group(body)) if body.delimiter() == Delimiter::Brace => body,
+            _ => return None,
+        };
+        let output = return_type(&signature)?;
+        Some(Function {
+            signature,
+            output,
+            body,
+        })
+    }
+
+    /// The function as it was.
+    pub fn into_item(self) -> TokenStream {
+        let body = self.body.stream();
+        self.with_body(body)
+    }
+
+    /// The function with its body replaced by `body`.
+    pub fn with_body(self, body: TokenStream) -> TokenStream {
+        let mut item: TokenStream = self.signature.into_iter().collect();
+        item.extend(Some(group(Delimiter::Brace, body)));
+        item
+    }
+}
+
+// The tokens after `->` and before any `where` clause, `Some(None)` if
+// there is no `->`, or `None` if the item is not a function.
+fn return_type(signature: &[TokenTree]) -> Option<Option<TokenStream>> {
+    let start = signature.iter().position(|token| is_ident(token, "fn"))?;
+    // After `fn` and the name.
+    let mut tokens = signature[start..].iter().skip(2);
+    // Generic parameters may contain `->` and parentheses, as in
+    // `F: Fn(u32) -> u32`, so the parameters are the first parenthesis
+    // outside the angle brackets.
+    let mut depth = 0;
+    let mut previous = None;
+    let mut parameters = false;
+    for token in tokens.by_ref() {
+        match token {
+            TokenTree::Punct(punct) if punct.as_char() == '<' => depth += 1,
+            TokenTree::Punct(punct) if punct.as_char() == '>' && !is_arrow_head(previous) => {
+                depth -= 1
+            }
+            TokenTree::Group(group)
+                if depth == 0 && group.delimiter() == Delimiter::Parenthesis =>
+            {
+                parameters = true;
+                break;
+            }
+            _ => {}
+        }
+        previous = Some(token);
+    }
+    if !parameters {
+        return None;
+    }
+    match (tokens.next(), tokens.next()) {
+        (Some(TokenTree::Punct(minus)), Some(TokenTree::Punct(greater)))
+            if minus.as_char() == '-' && greater.as_char() == '>' =>
+        {
+            Some(Some(
+                tokens
+                    .take_while(|token| !is_ident(token, "where"))
+                    .cloned()
+                    .collect(),
+            ))
+        }
+        (None, _) => Some(None),
+        (Some(token), _) if is_ident(token, "where") => Some(None),
+        _ => None,
+    }
+}
+
+fn is_ident(token: &TokenTree, name: &str) -> bool {
+    matches!(token, TokenTree::Ident(ident) if ident.to_string() == name)
+}
+
+fn is_arrow_head(previous: Option<&TokenTree>) -> bool {
+    matches!(
+        previous,
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '-' && punct.spacing() == Spacing::Joint
+    )
+}
+
+/// A postcondition: either `|ret| CONDITION` on a reference to the
+/// function's result, or a condition that does not mention it.
+pub enum Postcondition {
+    Closure {
+        parameter: TokenStream,
+        condition: TokenStream,
+    },
+    Condition(TokenStream),
+}
+
+impl Postcondition {
+    pub fn parse(attr: TokenStream) -> Option<Postcondition> {
+        let tokens: Vec<TokenTree> = attr.clone().into_iter().collect();
+        if !matches!(tokens.first(), Some(TokenTree::Punct(bar)) if bar.as_char() == '|') {
+            return Some(Postcondition::Condition(attr));
+        }
+        let close = tokens
+            .iter()
+            .skip(1)
+            .position(|token| matches!(token, TokenTree::Punct(bar) if bar.as_char() == '|'))?
+            + 1;
+        Some(Postcondition::Closure {
+            parameter: tokens[1..close].iter().cloned().collect(),
+            condition: tokens[close + 1..].iter().cloned().collect(),
+        })
+    }
+
+    // A `bool` expression on `verifier_result`, the function's result.
+    fn holds(&self) -> TokenStream {
+        match self {
+            Postcondition::Closure {
+                parameter,
+                condition,
+            } => {
+                let mut arguments: TokenStream = "&verifier_result, |".parse().unwrap();
+                arguments.extend(parameter.clone());
+                arguments.extend("|".parse::<TokenStream>().unwrap());
+                arguments.extend(condition.clone());
+                let mut holds: TokenStream = "::verifier::contract_holds".parse().unwrap();
+                holds.extend(Some(group(Delimiter::Parenthesis, arguments)));
+                holds
+            }
+            Postcondition::Condition(condition) => {
+                group(Delimiter::Parenthesis, condition.clone()).into()
+            }
+        }
+    }
+
+    // The postcondition as written, for messages.
+    fn written(&self) -> String {
+        match self {
+            Postcondition::Closure {
+                parameter,
+                condition,
+            } => format!("|{}| {}", parameter, condition),
+            Postcondition::Condition(condition) => condition.to_string(),
+        }
+    }
+
+    // The condition for Prusti, which names the result `result`.
+    fn prusti(&self) -> TokenStream {
+        match self {
+            Postcondition::Closure {
+                parameter,
+                condition,
+            } => {
+                let name = parameter.clone().into_iter().next();
+                match name {
+                    Some(TokenTree::Ident(name)) => {
+                        replace_ident(condition.clone(), &name.to_string(), "(&result)")
+                    }
+                    _ => condition.clone(),
+                }
+            }
+            Postcondition::Condition(condition) => condition.clone(),
+        }
+    }
+}
+
+fn replace_ident(tokens: TokenStream, name: &str, replacement: &str) -> TokenStream {
+    tokens
+        .into_iter()
+        .flat_map(|token| -> TokenStream {
+            match token {
+                TokenTree::Ident(ref ident) if ident.to_string() == name => {
+                    replacement.parse().unwrap()
+                }
+                TokenTree::Group(group) => {
+                    let mut replaced = Group::new(
+                        group.delimiter(),
+                        replace_ident(group.stream(), name, replacement),
+                    );
+                    replaced.set_span(group.span());
+                    TokenTree::Group(replaced).into()
+                }
+                token => token.into(),
+            }
+        })
+        .collect()
+}
+
+/// Expand `#[requires(condition)]` on `function`.
+pub fn requires(condition: TokenStream, function: Function) -> TokenStream {
+    if cfg!(feature = "prusti") {
+        let mut item = attribute("prusti_contracts::requires", condition);
+        item.extend(function.into_item());
+        return item;
+    }
+    let check = if cfg!(feature = "mirai") {
+        "::mirai_annotations::precondition!"
+    } else {
+        "::verifier::assume!"
+    };
+    let mut body = statement(check, condition);
+    body.extend(function.body.stream());
+    function.with_body(body)
+}
+
+/// Expand `#[ensures(postcondition)]` on `function`.
+pub fn ensures(postcondition: Postcondition, function: Function) -> TokenStream {
+    if cfg!(feature = "prusti") {
+        let mut item = attribute("prusti_contracts::ensures", postcondition.prusti());
+        item.extend(function.into_item());
+        return item;
+    }
+
+    let check = if cfg!(feature = "mirai") {
+        statement("::mirai_annotations::postcondition!", postcondition.holds())
+    } else {
+        // The message gives the postcondition as written, since the
+        // condition that is checked is rewritten.
+        let mut arguments = postcondition.holds();
+        arguments.extend(
+            format!(
+                ", \"postcondition `{{}}` does not hold\", {:?}",
+                postcondition.written()
+            )
+            .parse::<TokenStream>()
+            .unwrap(),
+        );
+        statement("::verifier::assert!", arguments)
+    };
+    // The body runs in a closure so that `return` and `?` leave it with
+    // the result that the postcondition checks. A closure cannot return
+    // `impl Trait`, so then its type is inferred.
+    let output = match &function.output {
+        Some(output) if !output.to_string().contains("impl") => format!("-> {}", output),
+        Some(_) => String::new(),
+        None => "-> ()".to_string(),
+    };
+    let mut closure: TokenStream = format!("|| {}", output).parse().unwrap();
+    closure.extend(Some(TokenTree::Group(function.body.clone())));
+    let mut body: TokenStream = "#[allow(clippy::redundant_closure_call)] let verifier_result = "
+        .parse()
+        .unwrap();
+    body.extend(Some(group(Delimiter::Parenthesis, closure)));
+    body.extend("(); ".parse::<TokenStream>().unwrap());
+    body.extend(check);
+    body.extend("verifier_result".parse::<TokenStream>().unwrap());
+    function.with_body(body)
+}
+
+fn group(delimiter: Delimiter, tokens: TokenStream) -> TokenTree {
+    TokenTree::Group(Group::new(delimiter, tokens))
+}
+
+// `MACRO(arguments);`
+fn statement(path: &str, arguments: TokenStream) -> TokenStream {
+    let mut statement: TokenStream = path.parse().unwrap();
+    statement.extend(Some(group(Delimiter::Parenthesis, arguments)));
+    statement.extend(";".parse::<TokenStream>().unwrap());
+    statement
+}
+
+// `#[path(arguments)]`
+fn attribute(path: &str, arguments: TokenStream) -> TokenStream {
+    let mut attribute: TokenStream = path.parse().unwrap();
+    attribute.extend(Some(group(Delimiter::Parenthesis, arguments)));
+    let mut item: TokenStream = "#".parse().unwrap();
+    item.extend(Some(group(Delimiter::Bracket, attribute)));
+    item
+}
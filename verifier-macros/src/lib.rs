@@ -0,0 +1,125 @@
+//! Attribute macros for the `verifier` crate, which re-exports them.
+//!
+//! These are written against `proc_macro` alone so that the verifier
+//! crates keep building without any registry dependencies.
+
+extern crate proc_macro;
+
+mod contract;
+
+use contract::{Function, Postcondition};
+use proc_macro::{TokenStream, TokenTree};
+
+/// Mark a function as a verification harness.
+///
+/// A harness takes no arguments and returns nothing. Natively it is run
+/// by a `#[test]` named `verifier_harness_NAME`, many times over with the
+/// `verifier-random` feature of `verifier`. When built with the
+/// `verifier-klee` feature, `verifier_harness_NAME` is instead exported
+/// as the entry point for KLEE.
+///
+/// ```ignore
+/// #[verifier::harness]
+/// fn add_commutes() {
+///     let (a, b) = (verifier::nondet!(0u8), verifier::nondet!(0u8));
+///     verifier::assert_eq!(a.wrapping_add(b), b.wrapping_add(a));
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn harness(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return error("`#[verifier::harness]` takes no arguments");
+    }
+    let name = match function_name(item.clone()) {
+        Some(name) => name,
+        None => return error("`#[verifier::harness]` can only be applied to a function"),
+    };
+
+    let mut harness: TokenStream = "#[allow(dead_code)]".parse().unwrap();
+    harness.extend(item);
+    harness.extend(
+        format!(
+            r#"
+            #[cfg(not(feature = "verifier-klee"))]
+            #[test]
+            fn verifier_harness_{name}() {{
+                ::verifier::native::run({name});
+            }}
+
+            #[cfg(feature = "verifier-klee")]
+            #[no_mangle]
+            pub extern "C" fn verifier_harness_{name}() {{
+                ::verifier::klee_annotations::verifier_set_panic_hook();
+                {name}();
+            }}
+            "#,
+            name = name
+        )
+        .parse::<TokenStream>()
+        .unwrap(),
+    );
+    harness
+}
+
+/// A precondition of a function, which is assumed when it is entered.
+///
+/// ```ignore
+/// #[verifier::requires(a <= u32::MAX / 2)]
+/// fn double(a: u32) -> u32 {
+///     a * 2
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn requires(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if attr.is_empty() {
+        return error("`#[verifier::requires]` takes a condition");
+    }
+    match Function::parse(item) {
+        Some(function) => contract::requires(attr, function),
+        None => error("`#[verifier::requires]` can only be applied to a function with a body"),
+    }
+}
+
+/// A postcondition of a function, which is asserted when it returns.
+///
+/// The condition is either a closure that takes a reference to the
+/// result, or an expression that does not depend on it.
+///
+/// ```ignore
+/// #[verifier::ensures(|ret| *ret == 2 * a)]
+/// fn double(a: u32) -> u32 {
+///     a * 2
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn ensures(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let postcondition = match Postcondition::parse(attr.clone()) {
+        Some(postcondition) if !attr.is_empty() => postcondition,
+        _ => return error("`#[verifier::ensures]` takes a condition or `|ret| condition`"),
+    };
+    match Function::parse(item) {
+        Some(function) => contract::ensures(postcondition, function),
+        None => error("`#[verifier::ensures]` can only be applied to a function with a body"),
+    }
+}
+
+// The identifier after `fn`, skipping attributes, visibility and
+// qualifiers such as `unsafe`.
+fn function_name(item: TokenStream) -> Option<String> {
+    let mut tokens = item.into_iter();
+    while let Some(token) = tokens.next() {
+        if let TokenTree::Ident(ident) = token {
+            if ident.to_string() == "fn" {
+                return match tokens.next() {
+                    Some(TokenTree::Ident(name)) => Some(name.to_string()),
+                    _ => None,
+                };
+            }
+        }
+    }
+    None
+}
+
+fn error(message: &str) -> TokenStream {
+    format!("compile_error!({:?});", message).parse().unwrap()
+}
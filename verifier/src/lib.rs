@@ -2,64 +2,433 @@
 macro_rules! assume {
     ($condition:expr) => {
         if cfg!(feature = "verifier-klee") {
-            klee_annotations::verifier_assume($condition)
+            $crate::klee_annotations::verifier_assume($condition)
         } else {
-            assert!($condition);
+            $crate::native::assume($condition)
         }
     };
 }
 
+/// Assert that a condition holds, with an optional message formatted as
+/// by `format!`.
+///
+/// Under KLEE a failure reports the message and the condition.
 #[macro_export]
 macro_rules! assert {
-    ($condition:expr) => {
+    ($condition:expr $(,)?) => {
+        if cfg!(feature = "verifier-klee") {
+            if !$condition {
+                $crate::assert_failed((file!(), line!(), column!()), stringify!($condition), None)
+            }
+        } else {
+            ::std::assert!($condition);
+        }
+    };
+    ($condition:expr, $($arg:tt)+) => {
         if cfg!(feature = "verifier-klee") {
-            klee_annotations::verifier_verify($condition)
+            if !$condition {
+                $crate::assert_failed((file!(), line!(), column!()), stringify!($condition), Some(format_args!($($arg)+)))
+            }
         } else {
-            assert!($condition);
+            ::std::assert!($condition, $($arg)+);
         }
     };
 }
 
+/// Assert that two values are equal, with an optional message formatted
+/// as by `format!`.
+///
+/// Under KLEE a failure reports the message and both values, unless they
+/// are symbolic.
 #[macro_export]
 macro_rules! assert_eq {
-    ($left:expr, $right:expr) => (
-        if cfg!(feature = "verifier-klee") {
-            klee_annotations::verifier_verify($left == $right)
-        } else {
-            assert_eq!($left, $right);
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if cfg!(feature = "verifier-klee") {
+                    if !(*left == *right) {
+                        $crate::assert_compare_failed((file!(), line!(), column!()), "==", left, right, None)
+                    }
+                } else {
+                    ::std::assert_eq!(*left, *right);
+                }
+            }
         }
-    );
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if cfg!(feature = "verifier-klee") {
+                    if !(*left == *right) {
+                        $crate::assert_compare_failed((file!(), line!(), column!()), "==", left, right, Some(format_args!($($arg)+)))
+                    }
+                } else {
+                    ::std::assert_eq!(*left, *right, $($arg)+);
+                }
+            }
+        }
+    };
 }
 
+/// Assert that two values are not equal, with an optional message
+/// formatted as by `format!`.
+///
+/// Under KLEE a failure reports the message and both values, unless they
+/// are symbolic.
 #[macro_export]
 macro_rules! assert_ne {
-    ($left:expr, $right:expr) => (
-        if cfg!(feature = "verifier-klee") {
-            klee_annotations::verifier_verify($left != $right)
-        } else {
-            assert!($left != $right);
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if cfg!(feature = "verifier-klee") {
+                    if *left == *right {
+                        $crate::assert_compare_failed((file!(), line!(), column!()), "!=", left, right, None)
+                    }
+                } else {
+                    ::std::assert_ne!(*left, *right);
+                }
+            }
         }
-    );
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if cfg!(feature = "verifier-klee") {
+                    if *left == *right {
+                        $crate::assert_compare_failed((file!(), line!(), column!()), "!=", left, right, Some(format_args!($($arg)+)))
+                    }
+                } else {
+                    ::std::assert_ne!(*left, *right, $($arg)+);
+                }
+            }
+        }
+    };
 }
 
 #[macro_export]
 macro_rules! unreachable {
     () => (
         if cfg!(feature = "verifier-klee") {
-            klee_annotations::verifier_report_error("unreachable");
+            $crate::klee_annotations::verifier_report_error_at(file!(), line!(), column!(), "unreachable");
         } else {
             unreachable!();
         }
     );
 }
 
+/// Mark a point that some run should reach with `condition` true.
+///
+/// A verified program whose cover points are never covered may only
+/// verify because its assumptions rule out every run. Covered points are
+/// printed as `verifier: covered LABEL`, which `bench-runner` checks.
+///
+/// ```ignore
+/// verifier::cover!(a > b, "a is larger");
+/// ```
 #[macro_export]
-macro_rules! nondet {
-    ($value:expr) => {
+macro_rules! cover {
+    ($condition:expr, $label:expr $(,)?) => {
         if cfg!(feature = "verifier-klee") {
-            klee_annotations::verifier_abstract_value($value)
+            $crate::klee_annotations::verifier_cover($condition, $label)
         } else {
-            $value
+            $crate::native::cover($condition, $label)
         }
     };
 }
+
+/// A loop invariant, placed at the start of the body of a loop.
+///
+/// KLEE and native runs check it at the start of every iteration, which
+/// only covers the iterations that they explore. With `verifier-prusti`
+/// it is Prusti's `body_invariant!`, which proves it for all of them.
+///
+/// ```ignore
+/// for i in 0..n {
+///     verifier::invariant!(2 * sum + i == i * i);
+///     sum += i;
+/// }
+/// ```
+#[cfg(not(feature = "verifier-prusti"))]
+#[macro_export]
+macro_rules! invariant {
+    ($condition:expr $(,)?) => {
+        $crate::assert!(
+            $condition,
+            "loop invariant `{}` does not hold",
+            stringify!($condition)
+        )
+    };
+}
+
+#[cfg(feature = "verifier-prusti")]
+#[macro_export]
+macro_rules! invariant {
+    ($condition:expr $(,)?) => {
+        ::prusti_contracts::body_invariant!($condition)
+    };
+}
+
+/// Whether a condition holds for every element of a bounded domain, such
+/// as a range or a collection.
+///
+/// KLEE and native runs loop over the domain. With `verifier-prusti` this
+/// is Prusti's `forall` over a `usize` index, for use in contracts and loop
+/// invariants, so the domain must be a range `LO..HI` whose lower bound is a
+/// literal, a name or a parenthesized expression.
+///
+/// ```ignore
+/// verifier::assert!(verifier::forall!(i in 0..v.len() => v[i] > 0));
+/// ```
+#[cfg(not(feature = "verifier-prusti"))]
+#[macro_export]
+macro_rules! forall {
+    ($x:pat in $domain:expr => $condition:expr) => {
+        ::core::iter::IntoIterator::into_iter($domain).all(|$x| $condition)
+    };
+}
+
+#[cfg(feature = "verifier-prusti")]
+#[macro_export]
+macro_rules! forall {
+    ($x:ident in $lo:tt .. $hi:expr => $condition:expr) => {
+        ::prusti_contracts::forall(|$x: usize| !($lo <= $x && $x < $hi) || $condition)
+    };
+}
+
+/// Whether a condition holds for some element of a bounded domain, such
+/// as a range or a collection.
+///
+/// KLEE and native runs loop over the domain. With `verifier-prusti` this
+/// is Prusti's `exists`, which takes the same `LO..HI` ranges as
+/// [`forall!`].
+///
+/// ```ignore
+/// verifier::assert!(verifier::exists!(x in &v => *x == 0));
+/// ```
+#[cfg(not(feature = "verifier-prusti"))]
+#[macro_export]
+macro_rules! exists {
+    ($x:pat in $domain:expr => $condition:expr) => {
+        ::core::iter::IntoIterator::into_iter($domain).any(|$x| $condition)
+    };
+}
+
+#[cfg(feature = "verifier-prusti")]
+#[macro_export]
+macro_rules! exists {
+    ($x:ident in $lo:tt .. $hi:expr => $condition:expr) => {
+        ::prusti_contracts::exists(|$x: usize| $lo <= $x && $x < $hi && $condition)
+    };
+}
+
+/// A nondeterministic value.
+///
+/// `nondet!()` and `nondet!(type T)` produce a value of any type with a
+/// `Symbolic` implementation, which is the default value in native runs
+/// without `verifier-random`. The older `nondet!(VALUE)` form takes a
+/// placeholder of a `Symbolic` type that only native runs use. A type is
+/// marked with `type` because `nondet!(T)` could not be told apart from
+/// `nondet!(VALUE)` with a value named by a path, such as `nondet!(x)`.
+///
+/// ```ignore
+/// let x: u32 = verifier::nondet!();
+/// let c = verifier::nondet!(type char);
+/// ```
+#[macro_export]
+macro_rules! nondet {
+    () => {
+        $crate::symbolic()
+    };
+    (type $t:ty) => {
+        $crate::symbolic::<$t>()
+    };
+    ($value:expr) => {
+        $crate::nondet_value($value)
+    };
+}
+
+/// Give an existing place of a `Symbolic` type, such as `&mut x`, an
+/// arbitrary new value, as code outside the verification scope might.
+///
+/// Under KLEE the new value is symbolic and with `verifier-random` it is
+/// pseudo-random. Other native runs leave the value unchanged, just as
+/// `nondet!` returns its placeholder.
+#[macro_export]
+macro_rules! havoc {
+    ($place:expr) => {
+        $crate::havoc($place)
+    };
+}
+
+/// A nondeterministic integer in a range such as `lo..hi`.
+///
+/// Under KLEE this is a symbolic value constrained to the range, with
+/// `verifier-random` it is chosen uniformly, and in other native runs it
+/// is the start of the range. An empty range rejects the run.
+#[macro_export]
+macro_rules! nondet_range {
+    ($range:expr) => {{
+        let range: ::core::ops::Range<_> = $range;
+        $crate::nondet_in_range(range.start, range.end)
+    }};
+}
+
+/// One of the elements of an array such as `[a, b, c]`, chosen
+/// nondeterministically as by `nondet_range!`.
+#[macro_export]
+macro_rules! nondet_in {
+    ($values:expr) => {{
+        let values = $values;
+        let index = $crate::nondet_in_range(0, values.len());
+        ::core::iter::IntoIterator::into_iter(values)
+            .nth(index)
+            .unwrap()
+    }};
+}
+
+pub use symbolic::Symbolic;
+pub use verifier_macros::{ensures, harness, requires};
+use std::fmt;
+
+#[doc(hidden)]
+#[cfg(any(feature = "verifier-klee", feature = "verifier-random"))]
+pub fn symbolic<T: Symbolic>() -> T {
+    T::symbolic()
+}
+
+#[doc(hidden)]
+#[cfg(any(feature = "verifier-klee", feature = "verifier-random"))]
+pub fn nondet_value<T: Symbolic>(_placeholder: T) -> T {
+    T::symbolic()
+}
+
+#[doc(hidden)]
+#[cfg(not(any(feature = "verifier-klee", feature = "verifier-random")))]
+pub fn nondet_value<T: Symbolic>(placeholder: T) -> T {
+    placeholder
+}
+
+#[doc(hidden)]
+#[cfg(any(feature = "verifier-klee", feature = "verifier-random"))]
+pub fn havoc<T: Symbolic>(place: &mut T) {
+    place.havoc()
+}
+
+#[doc(hidden)]
+#[cfg(not(any(feature = "verifier-klee", feature = "verifier-random")))]
+pub fn havoc<T: Symbolic>(_place: &mut T) {}
+
+#[doc(hidden)]
+#[cfg(any(feature = "verifier-klee", feature = "verifier-random"))]
+pub fn nondet_in_range<T: klee_annotations::VerifierInteger>(start: T, end: T) -> T {
+    klee_annotations::verifier_range(start, end)
+}
+
+#[doc(hidden)]
+#[cfg(not(any(feature = "verifier-klee", feature = "verifier-random")))]
+pub fn nondet_in_range<T: klee_annotations::VerifierInteger>(start: T, end: T) -> T {
+    native::assume(start < end);
+    start
+}
+
+// The symbolic crate only generates values under KLEE or with
+// verifier-random.
+#[doc(hidden)]
+#[cfg(not(any(feature = "verifier-klee", feature = "verifier-random")))]
+pub fn symbolic<T: Symbolic + Default>() -> T {
+    T::default()
+}
+
+// The file, line and column of a failure.
+type SourceLocation = (&'static str, u32, u32);
+
+// Report a failed `assert!` to KLEE.
+#[doc(hidden)]
+pub fn assert_failed(
+    location: SourceLocation,
+    condition: &str,
+    message: Option<fmt::Arguments>,
+) -> ! {
+    let report = match message {
+        Some(message) => format!("assertion failed: {}: {}", condition, message),
+        None => format!("assertion failed: {}", condition),
+    };
+    report_error(location, &report)
+}
+
+// Report a failed `assert_eq!` or `assert_ne!` to KLEE. Formatting a
+// symbolic value would fork a path for each of its digits, so the values
+// are only shown when they are concrete.
+#[doc(hidden)]
+pub fn assert_compare_failed<T, U>(
+    location: SourceLocation,
+    op: &str,
+    left: &T,
+    right: &U,
+    message: Option<fmt::Arguments>,
+) -> !
+where
+    T: fmt::Debug + ?Sized,
+    U: fmt::Debug + ?Sized,
+{
+    let mut report = format!("assertion `left {} right` failed", op);
+    if let Some(message) = message {
+        report.push_str(&format!(": {}", message));
+    }
+    if klee_annotations::verifier_is_concrete(left) && klee_annotations::verifier_is_concrete(right) {
+        report.push_str(&format!("\n  left: {:?}\n right: {:?}", left, right));
+    }
+    report_error(location, &report)
+}
+
+fn report_error((file, line, column): SourceLocation, message: &str) -> ! {
+    klee_annotations::verifier_report_error_at(file, line, column, message)
+}
+
+// Used by `#[ensures]` so that the closure's parameter has a type.
+#[doc(hidden)]
+pub fn contract_holds<T: ?Sized, F: FnOnce(&T) -> bool>(result: &T, condition: F) -> bool {
+    condition(result)
+}
+
+// Used by the macros and by the code that `#[harness]` generates, so
+// that crates using them need not depend on klee-annotations themselves.
+#[doc(hidden)]
+pub use klee_annotations;
+
+/// Native execution of the macros, when not built for KLEE.
+///
+/// `assume!` rejects the run when its condition is false, which is a
+/// vacuous pass rather than a failure: a harness returns, and a program
+/// outside a harness exits with `VERIFIER_REJECTED_EXIT_CODE`.
+///
+/// With the `verifier-random` feature, `nondet!` returns pseudo-random
+/// values and each harness runs many times (set by `VERIFIER_ITERATIONS`,
+/// 1000 by default) from the seed in `VERIFIER_SEED`. A failing run
+/// prints the seed that reproduces it.
+#[doc(hidden)]
+pub mod native {
+    #[cfg(all(feature = "verifier-random", not(feature = "verifier-klee")))]
+    pub use crate::klee_annotations::{verifier_cover as cover, verifier_run as run};
+
+    /// Run a harness, or the `main` of a benchmark.
+    #[cfg(not(all(feature = "verifier-random", not(feature = "verifier-klee"))))]
+    pub fn run(harness: fn()) {
+        if !crate::klee_annotations::verifier_run_harness(harness) {
+            eprintln!("verifier: run rejected by an assumption");
+        }
+    }
+
+    #[cfg(not(all(feature = "verifier-random", not(feature = "verifier-klee"))))]
+    pub fn cover(condition: bool, label: &str) {
+        if condition {
+            crate::klee_annotations::verifier_count_cover(label)
+        }
+    }
+
+    pub fn assume(condition: bool) {
+        if !condition {
+            crate::klee_annotations::verifier_reject_run()
+        }
+    }
+}